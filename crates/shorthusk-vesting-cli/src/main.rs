@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use anchor_spl::associated_token::{
+    get_associated_token_address,
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+};
+use anchor_spl::token;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use clap::{Args, Parser, Subcommand};
+use shorthusk_vesting_client::import::{self, ImportReport};
+use shorthusk_vesting_client::{decode_error_message, instructions, pda, state, Schedule};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// Admin CLI for shorthusk-vesting vaults and grants. Amounts are in the mint's base units.
+#[derive(Parser)]
+#[command(name = "vesting-cli", version)]
+struct Cli {
+    /// RPC endpoint.
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair that signs as admin and pays fees; defaults to ~/.config/solana/id.json.
+    #[arg(long, global = true)]
+    keypair: Option<PathBuf>,
+    /// Print each serialized (unsigned) transaction as base64 instead of sending it. The recent blockhash is
+    /// left zeroed; RPC is only used to read the vault for the `batch-create` solvency check.
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct GrantArgs {
+    #[arg(long)]
+    mint: Pubkey,
+    #[arg(long)]
+    beneficiary: Pubkey,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates the vault for a mint together with its associated token account.
+    InitVault {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Transfers tokens into the vault.
+    Fund {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// Token account to fund from; defaults to the signer's associated token account.
+        #[arg(long)]
+        source: Option<Pubkey>,
+    },
+    /// Creates a single vesting grant.
+    CreateGrant {
+        #[command(flatten)]
+        grant: GrantArgs,
+        /// Unix timestamp at which vesting starts.
+        #[arg(long)]
+        start_time: i64,
+        /// Seconds after the start before anything unlocks.
+        #[arg(long)]
+        cliff_period: i64,
+        /// Seconds after the start until everything is unlocked.
+        #[arg(long)]
+        duration: i64,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        irrevocable: bool,
+    },
+    /// Creates grants from a CSV (header `beneficiary,amount,start_time,cliff_period,duration[,mint]`) or a
+    /// `.json` array of the same fields. Nothing is sent unless every row is valid and the vault can cover the
+    /// total; grants that already exist with the same parameters are skipped, so a failed run can be repeated.
+    BatchCreate {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Checks a grant file like `batch-create` does, including vault solvency, without sending anything.
+    ValidateGrants {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Pauses claims on a grant.
+    Pause {
+        #[command(flatten)]
+        grant: GrantArgs,
+    },
+    /// Resumes claims on a paused grant.
+    Unpause {
+        #[command(flatten)]
+        grant: GrantArgs,
+    },
+    /// Revokes a grant, sending its unclaimed tokens to the recovery destination.
+    Revoke {
+        #[command(flatten)]
+        grant: GrantArgs,
+        #[arg(long)]
+        recovery_destination: Pubkey,
+    },
+    /// Unlocks and pays out everything left in a grant.
+    InstantUnlock {
+        #[command(flatten)]
+        grant: GrantArgs,
+        /// Token account to pay out to; defaults to the beneficiary's associated token account.
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Transfers the vault's admin role.
+    UpdateAdmin {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        new_admin: Pubkey,
+    },
+    /// Moves every token not owed to irrevocable grants to the recovery destination.
+    EmergencyRecover {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        recovery_destination: Pubkey,
+    },
+}
+
+/// Reads and validates a grant file, leaves out grants that already exist with the same parameters, and
+/// checks that the vault's surplus, as reported by `get_vault_summary`, covers the rest. Prints every
+/// violation and fails if there are any.
+fn import_grants(path: &PathBuf, mint: &Pubkey, rpc: &RpcClient) -> Result<ImportReport> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut report = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => import::read_json(file, mint)?,
+        _ => import::read_csv(file, mint)?,
+    };
+    let mut existing = HashMap::new();
+    // `getMultipleAccounts` takes at most 100 addresses.
+    for grants in report.grants.chunks(100) {
+        let addresses: Vec<Pubkey> = grants
+            .iter()
+            .map(|grant| pda::vesting(mint, &grant.beneficiary).0)
+            .collect();
+        for (grant, account) in grants.iter().zip(rpc.get_multiple_accounts(&addresses)?) {
+            if let Some(account) = account {
+                let vesting = state::vesting_account(&account.data)
+                    .with_context(|| format!("decoding the existing grant of {}", grant.beneficiary))?;
+                existing.insert(grant.beneficiary, vesting);
+            }
+        }
+    }
+    report.exclude_existing(&existing);
+    let vault = state::vault(&rpc.get_account_data(&pda::vault(mint).0)?)?;
+    let balance = rpc
+        .get_token_account_balance(&pda::vault_token_account(mint))?
+        .amount
+        .parse::<u64>()?;
+    report.check_solvency(u64::try_from(vault.surplus(balance).max(0)).unwrap_or(u64::MAX));
+    if !report.is_valid() {
+        for violation in &report.violations {
+            eprintln!("{violation}");
+        }
+        bail!("{} problems found in {}", report.violations.len(), path.display());
+    }
+    eprintln!(
+        "{} grants totalling {} in {} transactions; {} already exist",
+        report.grants.len(),
+        report.total_amount(),
+        report.batches().count(),
+        report.existing
+    );
+    Ok(report)
+}
+
+/// Builds the transactions for `command`, one per inner `Vec`.
+fn build(command: Command, signer: &Pubkey, rpc: &RpcClient) -> Result<Vec<Vec<Instruction>>> {
+    let transactions = match command {
+        Command::InitVault { mint } => vec![vec![
+            instructions::initialize_vault(signer, &mint),
+            create_associated_token_account_idempotent(signer, &pda::vault(&mint).0, &mint, &token::ID),
+        ]],
+        Command::Fund { mint, amount, source } => {
+            let source = source.unwrap_or_else(|| get_associated_token_address(signer, &mint));
+            vec![vec![instructions::fund_vault_existing(
+                signer, signer, &mint, &source, amount,
+            )]]
+        }
+        Command::CreateGrant {
+            grant,
+            start_time,
+            cliff_period,
+            duration,
+            amount,
+            irrevocable,
+        } => vec![vec![instructions::initialize_vesting(
+            signer,
+            signer,
+            &grant.mint,
+            &grant.beneficiary,
+            Schedule {
+                start_time,
+                cliff_period,
+                duration,
+            },
+            amount,
+            irrevocable,
+        )]],
+        Command::BatchCreate { mint, file } => import_grants(&file, &mint, rpc)?
+            .instructions(signer, &mint)
+            .into_iter()
+            .map(|instruction| vec![instruction])
+            .collect(),
+        Command::ValidateGrants { mint, file } => {
+            import_grants(&file, &mint, rpc)?;
+            Vec::new()
+        }
+        Command::Pause { grant } => vec![vec![instructions::pause(
+            signer,
+            &grant.mint,
+            &grant.beneficiary,
+        )]],
+        Command::Unpause { grant } => vec![vec![instructions::unpause(
+            signer,
+            &grant.mint,
+            &grant.beneficiary,
+        )]],
+        Command::Revoke {
+            grant,
+            recovery_destination,
+        } => vec![vec![instructions::revoke_vesting(
+            signer,
+            &grant.mint,
+            &grant.beneficiary,
+            &recovery_destination,
+        )]],
+        Command::InstantUnlock { grant, destination } => {
+            let destination = destination
+                .unwrap_or_else(|| get_associated_token_address(&grant.beneficiary, &grant.mint));
+            vec![vec![instructions::instant_unlock(
+                signer,
+                &grant.mint,
+                &grant.beneficiary,
+                &destination,
+            )]]
+        }
+        Command::UpdateAdmin { mint, new_admin } => {
+            vec![vec![instructions::update_admin(signer, &mint, &new_admin)]]
+        }
+        Command::EmergencyRecover {
+            mint,
+            recovery_destination,
+        } => vec![vec![instructions::emergency_recover(
+            signer,
+            &mint,
+            &recovery_destination,
+        )]],
+    };
+    Ok(transactions)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => PathBuf::from(std::env::var("HOME").context("HOME is not set")?)
+            .join(".config/solana/id.json"),
+    };
+    let keypair: Keypair = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {err}", keypair_path.display()))?;
+    let rpc = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
+    let transactions = build(cli.command, &keypair.pubkey(), &rpc)?;
+
+    if cli.dry_run {
+        for instructions in transactions {
+            let transaction = Transaction::new_with_payer(&instructions, Some(&keypair.pubkey()));
+            println!(
+                "{}",
+                base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction)?)
+            );
+        }
+        return Ok(());
+    }
+
+    for instructions in transactions {
+        let blockhash: Hash = rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&keypair.pubkey()),
+            &[&keypair],
+            blockhash,
+        );
+        match rpc.send_and_confirm_transaction(&transaction) {
+            Ok(signature) => println!("{signature}"),
+            Err(err) => {
+                return Err(match decode_error_message(&err.to_string()) {
+                    Some(error) => anyhow!("{}: {error}", error.name()),
+                    None => err.into(),
+                })
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Mapping of custom program error codes back to `VestingError`.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use shorthusk_vesting::VestingError;
+
+/// Every `VestingError` variant, in declaration order. The checks below fail to compile when the program gains a
+/// variant that is missing here.
+const ERRORS: &[VestingError] = &[
+    VestingError::CliffNotReached,
+    VestingError::NothingToClaim,
+    VestingError::NotInitialized,
+    VestingError::Paused,
+    VestingError::VaultPaused,
+    VestingError::NotEnoughAccounts,
+    VestingError::AlreadyInitialized,
+    VestingError::InvalidAccountOwner,
+    VestingError::Unauthorized,
+    VestingError::InvalidTimestamp,
+    VestingError::InvalidDuration,
+    VestingError::InvalidCliffPeriod,
+    VestingError::InvalidAmount,
+    VestingError::TooManyAccounts,
+    VestingError::VaultNotInitialized,
+    VestingError::VestingRevoked,
+    VestingError::InvalidAccount,
+    VestingError::NotRevoked,
+    VestingError::InvalidAccountCount,
+    VestingError::AlreadyTerminated,
+    VestingError::Irrevocable,
+    VestingError::InvalidGrantReduction,
+    VestingError::InvalidAmendment,
+    VestingError::AmendmentReducesVested,
+    VestingError::IncompatibleVesting,
+    VestingError::InvalidProof,
+    VestingError::DistributionExhausted,
+    VestingError::ConflictingVestingAccount,
+    VestingError::InvalidEndTime,
+    VestingError::LeafAlreadyClaimed,
+    VestingError::NonCanonicalBump,
+    VestingError::AlreadyMigrated,
+];
+
+/// Whether `error` is the last `VestingError` variant. The match has no wildcard, so adding a variant breaks it.
+const fn is_last(error: VestingError) -> bool {
+    match error {
+        VestingError::CliffNotReached
+        | VestingError::NothingToClaim
+        | VestingError::NotInitialized
+        | VestingError::Paused
+        | VestingError::VaultPaused
+        | VestingError::NotEnoughAccounts
+        | VestingError::AlreadyInitialized
+        | VestingError::InvalidAccountOwner
+        | VestingError::Unauthorized
+        | VestingError::InvalidTimestamp
+        | VestingError::InvalidDuration
+        | VestingError::InvalidCliffPeriod
+        | VestingError::InvalidAmount
+        | VestingError::TooManyAccounts
+        | VestingError::VaultNotInitialized
+        | VestingError::VestingRevoked
+        | VestingError::InvalidAccount
+        | VestingError::NotRevoked
+        | VestingError::InvalidAccountCount
+        | VestingError::AlreadyTerminated
+        | VestingError::Irrevocable
+        | VestingError::InvalidGrantReduction
+        | VestingError::InvalidAmendment
+        | VestingError::AmendmentReducesVested
+        | VestingError::IncompatibleVesting
+        | VestingError::InvalidProof
+        | VestingError::DistributionExhausted
+        | VestingError::ConflictingVestingAccount
+        | VestingError::InvalidEndTime
+        | VestingError::LeafAlreadyClaimed
+        | VestingError::NonCanonicalBump => false,
+        VestingError::AlreadyMigrated => true,
+    }
+}
+
+const _: () = {
+    let mut index = 0;
+    while index < ERRORS.len() {
+        assert!(ERRORS[index] as usize == index, "ERRORS must follow the declaration order");
+        index += 1;
+    }
+    assert!(is_last(ERRORS[ERRORS.len() - 1]), "ERRORS must end with the last variant");
+};
+
+/// Returns the `VestingError` for a custom program error code, e.g. `6001` for `NothingToClaim`.
+pub fn decode_error(code: u32) -> Option<VestingError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    ERRORS.get(index as usize).copied()
+}
+
+/// Finds a `custom program error: 0x..` in an RPC error message or log line and decodes it.
+pub fn decode_error_message(message: &str) -> Option<VestingError> {
+    let (_, code) = message.split_once("custom program error: 0x")?;
+    let code: String = code.chars().take_while(char::is_ascii_hexdigit).collect();
+    decode_error(u32::from_str_radix(&code, 16).ok()?)
+}
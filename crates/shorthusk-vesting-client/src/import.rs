@@ -0,0 +1,340 @@
+//! Import of grant tables for batch creation.
+//!
+//! Rows are read from CSV (header `beneficiary,amount,start_time,cliff_period,duration`, plus an optional
+//! `mint` column) or from a JSON array of objects with the same fields. Every row is checked against the rules
+//! `batch_initialize_vesting_idempotent` enforces, and all violations are collected instead of stopping at
+//! the first one. [`ImportReport::exclude_existing`] then sets aside grants an earlier run already created.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use serde::Deserialize;
+use shorthusk_vesting::{BatchVestingArgs, VestingAccount};
+use thiserror::Error;
+
+use crate::instructions;
+
+/// Maximum number of grants the program accepts in one batch instruction.
+pub const MAX_BATCH_SIZE: usize = 3;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A problem found while importing; `row` is 1-based and does not count the CSV header.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum Violation {
+    #[error("row {row}: {message}")]
+    Malformed { row: usize, message: String },
+    #[error("row {row}: invalid beneficiary `{value}`")]
+    InvalidBeneficiary { row: usize, value: String },
+    #[error("row {row}: mint `{value}` does not match the vault's mint")]
+    MintMismatch { row: usize, value: String },
+    #[error("row {row}: duration must be positive")]
+    InvalidDuration { row: usize },
+    #[error("row {row}: cliff period must be non-negative and not greater than duration")]
+    InvalidCliffPeriod { row: usize },
+    #[error("row {row}: amount must be positive")]
+    InvalidAmount { row: usize },
+    #[error("row {row}: beneficiary {beneficiary} already appears in row {first_row}")]
+    DuplicateBeneficiary {
+        row: usize,
+        first_row: usize,
+        beneficiary: Pubkey,
+    },
+    #[error("row {row}: beneficiary {beneficiary} already has a grant with different parameters")]
+    ConflictingGrant { row: usize, beneficiary: Pubkey },
+    #[error("grants total {required} but the vault only has {available} available")]
+    Insolvent { required: u128, available: u64 },
+}
+
+#[derive(Deserialize)]
+struct GrantRow {
+    beneficiary: String,
+    amount: u64,
+    start_time: i64,
+    cliff_period: i64,
+    duration: i64,
+    #[serde(default)]
+    mint: Option<String>,
+}
+
+/// The valid grants of an import together with every violation found.
+#[derive(Clone, Default)]
+pub struct ImportReport {
+    pub grants: Vec<BatchVestingArgs>,
+    pub violations: Vec<Violation>,
+    /// Valid grants left out because they already exist with the same parameters.
+    pub existing: usize,
+    first_rows: HashMap<Pubkey, usize>,
+}
+
+impl ImportReport {
+    /// Whether the import can be sent as is.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Sum of all valid grants.
+    pub fn total_amount(&self) -> u128 {
+        self.grants.iter().map(|grant| grant.total_amount as u128).sum()
+    }
+
+    /// Records a violation if the valid grants need more than `available` tokens, e.g. the vault's
+    /// balance minus what it already owes to existing grants.
+    pub fn check_solvency(&mut self, available: u64) {
+        let required = self.total_amount();
+        if required > available as u128 {
+            self.violations.push(Violation::Insolvent { required, available });
+        }
+    }
+
+    /// Leaves out grants that already exist with the same parameters, as after an interrupted run, so they are
+    /// neither sent nor counted against the vault again, and records a [`Violation::ConflictingGrant`] for
+    /// each existing vesting account that differs from its row. `accounts` maps beneficiaries to their
+    /// vesting account for the mint, where one exists.
+    pub fn exclude_existing(&mut self, accounts: &HashMap<Pubkey, VestingAccount>) {
+        let grants_before = self.grants.len();
+        let mut conflicts = Vec::new();
+        self.grants.retain(|grant| {
+            let Some(account) = accounts.get(&grant.beneficiary) else {
+                return true;
+            };
+            if !account.matches(grant) {
+                conflicts.push(Violation::ConflictingGrant {
+                    row: self.first_rows[&grant.beneficiary],
+                    beneficiary: grant.beneficiary,
+                });
+            }
+            false
+        });
+        self.existing += grants_before - self.grants.len() - conflicts.len();
+        self.violations.extend(conflicts);
+    }
+
+    /// Groups the valid grants into batches of at most [`MAX_BATCH_SIZE`], one per transaction.
+    pub fn batches(&self) -> impl Iterator<Item = &[BatchVestingArgs]> {
+        self.grants.chunks(MAX_BATCH_SIZE)
+    }
+
+    /// Builds one `batch_initialize_vesting_idempotent` instruction per batch, so a partially sent import
+    /// can be sent again without creating duplicates.
+    pub fn instructions(&self, admin: &Pubkey, mint: &Pubkey) -> Vec<Instruction> {
+        self.batches()
+            .map(|batch| instructions::batch_initialize_vesting_idempotent(admin, mint, batch.to_vec()))
+            .collect()
+    }
+
+    fn push_row(&mut self, row_number: usize, row: GrantRow, mint: &Pubkey) {
+        let violations_before = self.violations.len();
+        let beneficiary = row.beneficiary.trim().parse::<Pubkey>();
+        if beneficiary.is_err() {
+            self.violations.push(Violation::InvalidBeneficiary {
+                row: row_number,
+                value: row.beneficiary.clone(),
+            });
+        }
+        if let Some(value) = row.mint {
+            if value.trim().parse::<Pubkey>().ok() != Some(*mint) {
+                self.violations.push(Violation::MintMismatch { row: row_number, value });
+            }
+        }
+        if row.duration <= 0 {
+            self.violations.push(Violation::InvalidDuration { row: row_number });
+        }
+        if row.cliff_period < 0 || row.cliff_period > row.duration {
+            self.violations.push(Violation::InvalidCliffPeriod { row: row_number });
+        }
+        if row.amount == 0 {
+            self.violations.push(Violation::InvalidAmount { row: row_number });
+        }
+        let Ok(beneficiary) = beneficiary else {
+            return;
+        };
+        if let Some(&first_row) = self.first_rows.get(&beneficiary) {
+            self.violations.push(Violation::DuplicateBeneficiary {
+                row: row_number,
+                first_row,
+                beneficiary,
+            });
+            return;
+        }
+        self.first_rows.insert(beneficiary, row_number);
+
+        if self.violations.len() == violations_before {
+            self.grants.push(BatchVestingArgs {
+                beneficiary,
+                mint: *mint,
+                start_time: row.start_time,
+                cliff_period: row.cliff_period,
+                duration: row.duration,
+                total_amount: row.amount,
+            });
+        }
+    }
+}
+
+/// Imports grants for `mint` from CSV.
+pub fn read_csv<R: Read>(reader: R, mint: &Pubkey) -> Result<ImportReport, ImportError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    reader.headers()?;
+    let mut report = ImportReport::default();
+    for (i, row) in reader.deserialize::<GrantRow>().enumerate() {
+        match row {
+            Ok(row) => report.push_row(i + 1, row, mint),
+            Err(err) => report.violations.push(Violation::Malformed {
+                row: i + 1,
+                message: err.to_string(),
+            }),
+        }
+    }
+    Ok(report)
+}
+
+/// Imports grants for `mint` from a JSON array of row objects.
+pub fn read_json<R: Read>(reader: R, mint: &Pubkey) -> Result<ImportReport, ImportError> {
+    let rows: Vec<serde_json::Value> = serde_json::from_reader(reader)?;
+    let mut report = ImportReport::default();
+    for (i, row) in rows.into_iter().enumerate() {
+        match serde_json::from_value::<GrantRow>(row) {
+            Ok(row) => report.push_row(i + 1, row, mint),
+            Err(err) => report.violations.push(Violation::Malformed {
+                row: i + 1,
+                message: err.to_string(),
+            }),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_violation() {
+        let mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        let [a, b] = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let csv = format!(
+            "beneficiary,amount,start_time,cliff_period,duration,mint\n\
+             {a},100,0,10,100,\n\
+             {b},0,0,200,100,{mint}\n\
+             {a},100,0,10,100,\n\
+             nope,100,0,10,100,{other_mint}\n\
+             {b},abc,0,10,100,\n"
+        );
+        let report = read_csv(csv.as_bytes(), &mint).unwrap();
+
+        assert_eq!(report.grants.len(), 1);
+        assert_eq!(
+            report.violations[..5],
+            [
+                Violation::InvalidCliffPeriod { row: 2 },
+                Violation::InvalidAmount { row: 2 },
+                Violation::DuplicateBeneficiary {
+                    row: 3,
+                    first_row: 1,
+                    beneficiary: a
+                },
+                Violation::InvalidBeneficiary {
+                    row: 4,
+                    value: "nope".to_string()
+                },
+                Violation::MintMismatch {
+                    row: 4,
+                    value: other_mint.to_string()
+                },
+            ]
+        );
+        assert!(matches!(
+            report.violations[5..],
+            [Violation::Malformed { row: 5, .. }]
+        ));
+    }
+
+    #[test]
+    fn chunks_json_rows_and_checks_solvency() {
+        let mint = Pubkey::new_unique();
+        let rows: Vec<serde_json::Value> = (0..7)
+            .map(|_| {
+                serde_json::json!({
+                    "beneficiary": Pubkey::new_unique().to_string(),
+                    "amount": 1_000,
+                    "start_time": 1_700_000_000,
+                    "cliff_period": 0,
+                    "duration": 600,
+                })
+            })
+            .collect();
+        let mut report = read_json(serde_json::to_vec(&rows).unwrap().as_slice(), &mint).unwrap();
+
+        assert!(report.is_valid());
+        let sizes: Vec<usize> = report.batches().map(<[_]>::len).collect();
+        assert_eq!(sizes, vec![3, 3, 1]);
+        assert_eq!(report.instructions(&Pubkey::new_unique(), &mint).len(), 3);
+
+        report.check_solvency(6_999);
+        assert_eq!(
+            report.violations,
+            vec![Violation::Insolvent {
+                required: 7_000,
+                available: 6_999
+            }]
+        );
+    }
+
+    #[test]
+    fn leaves_out_existing_grants_before_checking_solvency() {
+        let mint = Pubkey::new_unique();
+        let [a, b, c] = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let csv = format!(
+            "beneficiary,amount,start_time,cliff_period,duration\n\
+             {a},100,0,10,100\n\
+             {b},200,0,10,100\n\
+             {c},300,0,10,100\n"
+        );
+        let mut report = read_csv(csv.as_bytes(), &mint).unwrap();
+        // `a` was created by an earlier run; `b` holds a grant with a different amount.
+        let existing = HashMap::from([
+            (a, VestingAccount::new(a, mint, 0, 10, 100, 100, 255, false)),
+            (b, VestingAccount::new(b, mint, 0, 10, 100, 250, 255, false)),
+        ]);
+        report.exclude_existing(&existing);
+        report.check_solvency(300);
+
+        assert_eq!(report.existing, 1);
+        assert_eq!(report.grants.len(), 1);
+        assert_eq!(report.grants[0].beneficiary, c);
+        assert_eq!(
+            report.violations,
+            vec![Violation::ConflictingGrant { row: 2, beneficiary: b }]
+        );
+    }
+
+    #[test]
+    fn irrevocable_grants_conflict_with_identical_rows() {
+        let mint = Pubkey::new_unique();
+        let beneficiary = Pubkey::new_unique();
+        let csv = format!("beneficiary,amount,start_time,cliff_period,duration\n{beneficiary},100,0,10,100\n");
+        let mut report = read_csv(csv.as_bytes(), &mint).unwrap();
+        // Same schedule and amount, but batch rows create revocable grants.
+        let existing = HashMap::from([(
+            beneficiary,
+            VestingAccount::new(beneficiary, mint, 0, 10, 100, 100, 255, true),
+        )]);
+        report.exclude_existing(&existing);
+
+        assert_eq!(report.existing, 0);
+        assert_eq!(
+            report.violations,
+            vec![Violation::ConflictingGrant { row: 1, beneficiary }]
+        );
+    }
+}
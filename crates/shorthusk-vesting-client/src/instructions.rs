@@ -0,0 +1,790 @@
+//! Typed builders for every program instruction.
+//!
+//! Builders take the wallets and token accounts that vary between calls and derive the program's PDAs and
+//! the vault's associated token account themselves. With the `event-cpi` feature, instructions that emit
+//! events also get the event authority and the program appended to their accounts. The deprecated
+//! `batch_initialize_vesting` (v1), only compiled with the program's `legacy-batch` feature, has no builder.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use shorthusk_vesting::{accounts, instruction, BatchScheduleArgs, BatchVestingArgs, BatchVestingEntry};
+
+use crate::pda;
+
+/// Start, cliff and duration of a grant, in seconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub start_time: i64,
+    pub cliff_period: i64,
+    pub duration: i64,
+}
+
+impl From<Schedule> for BatchScheduleArgs {
+    fn from(schedule: Schedule) -> Self {
+        Self {
+            start_time: schedule.start_time,
+            cliff_period: schedule.cliff_period,
+            duration: schedule.duration,
+        }
+    }
+}
+
+/// One grant of a `claim_many` instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimManyGrant {
+    pub vesting_account: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: shorthusk_vesting::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn with_remaining(mut ix: Instruction, remaining: impl IntoIterator<Item = AccountMeta>) -> Instruction {
+    ix.accounts.extend(remaining);
+    ix
+}
+
+pub fn initialize_vault(payer: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeVault {
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            payer: *payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::InitializeVault {},
+    )
+}
+
+pub fn initialize_vesting(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule: Schedule,
+    total_amount: u64,
+    irrevocable: bool,
+) -> Instruction {
+    build(
+        accounts::InitializeVesting {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            payer: *payer,
+            admin: *admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::InitializeVesting {
+            start_time: schedule.start_time,
+            cliff_period: schedule.cliff_period,
+            duration: schedule.duration,
+            total_amount,
+            irrevocable,
+        },
+    )
+}
+
+pub fn reinitialize_vesting(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule: Schedule,
+    total_amount: u64,
+) -> Instruction {
+    build(
+        accounts::ReinitializeVesting {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            payer: *payer,
+            admin: *admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::ReinitializeVesting {
+            start_time: schedule.start_time,
+            cliff_period: schedule.cliff_period,
+            duration: schedule.duration,
+            total_amount,
+        },
+    )
+}
+
+pub fn claim(beneficiary: &Pubkey, mint: &Pubkey, beneficiary_token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::Claim {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            beneficiary_token_account: *beneficiary_token_account,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            token_program: token::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::Claim {},
+    )
+}
+
+pub fn claim_many(beneficiary: &Pubkey, grants: &[ClaimManyGrant]) -> Instruction {
+    with_remaining(
+        build(
+            accounts::ClaimMany {
+                beneficiary: *beneficiary,
+                token_program: token::ID,
+                #[cfg(feature = "event-cpi")]
+                event_authority: pda::event_authority().0,
+                #[cfg(feature = "event-cpi")]
+                program: shorthusk_vesting::ID,
+            },
+            instruction::ClaimMany {},
+        ),
+        grants.iter().flat_map(|grant| {
+            [
+                AccountMeta::new(grant.vesting_account, false),
+                AccountMeta::new(pda::vault(&grant.mint).0, false),
+                AccountMeta::new(pda::vault_token_account(&grant.mint), false),
+                AccountMeta::new(grant.destination, false),
+            ]
+        }),
+    )
+}
+
+pub fn admin_claim(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    beneficiary_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::AdminClaim {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            beneficiary_token_account: *beneficiary_token_account,
+            mint: *mint,
+            token_program: token::ID,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::AdminClaim {},
+    )
+}
+
+pub fn get_claimable(mint: &Pubkey, beneficiary: &Pubkey) -> Instruction {
+    build(
+        accounts::GetClaimable {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            beneficiary: *beneficiary,
+        },
+        instruction::GetClaimable {},
+    )
+}
+
+/// `get_vesting_status` for any vesting account of `mint`, including ones created from a distribution.
+/// Decode the return data with [`crate::state::vesting_status`].
+pub fn get_vesting_status(mint: &Pubkey, vesting_account: &Pubkey) -> Instruction {
+    build(
+        accounts::GetVestingStatus {
+            vesting_account: *vesting_account,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+        },
+        instruction::GetVestingStatus {},
+    )
+}
+
+/// `get_vault_summary`. Decode the return data with [`crate::state::vault_summary`].
+pub fn get_vault_summary(mint: &Pubkey) -> Instruction {
+    build(
+        accounts::GetVaultSummary {
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            mint: *mint,
+        },
+        instruction::GetVaultSummary {},
+    )
+}
+
+fn pause_or_unpause(admin: &Pubkey, mint: &Pubkey, beneficiary: &Pubkey) -> accounts::PauseOrUnpause {
+    accounts::PauseOrUnpause {
+        vesting_account: pda::vesting(mint, beneficiary).0,
+        vault: pda::vault(mint).0,
+        mint: *mint,
+        admin: *admin,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: shorthusk_vesting::ID,
+    }
+}
+
+pub fn pause(admin: &Pubkey, mint: &Pubkey, beneficiary: &Pubkey) -> Instruction {
+    build(pause_or_unpause(admin, mint, beneficiary), instruction::Pause {})
+}
+
+pub fn unpause(admin: &Pubkey, mint: &Pubkey, beneficiary: &Pubkey) -> Instruction {
+    build(pause_or_unpause(admin, mint, beneficiary), instruction::Unpause {})
+}
+
+fn pause_vault_accounts(admin: &Pubkey, mint: &Pubkey) -> accounts::PauseVault {
+    accounts::PauseVault {
+        vault: pda::vault(mint).0,
+        mint: *mint,
+        admin: *admin,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: shorthusk_vesting::ID,
+    }
+}
+
+pub fn pause_vault(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(pause_vault_accounts(admin, mint), instruction::PauseVault {})
+}
+
+pub fn unpause_vault(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(pause_vault_accounts(admin, mint), instruction::UnpauseVault {})
+}
+
+fn revoke_accounts(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    recovery_destination: &Pubkey,
+) -> accounts::RevokeVesting {
+    accounts::RevokeVesting {
+        vesting_account: pda::vesting(mint, beneficiary).0,
+        vault: pda::vault(mint).0,
+        vault_token_account: pda::vault_token_account(mint),
+        recovery_destination: *recovery_destination,
+        mint: *mint,
+        token_program: token::ID,
+        admin: *admin,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: shorthusk_vesting::ID,
+    }
+}
+
+pub fn revoke_vesting(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    recovery_destination: &Pubkey,
+) -> Instruction {
+    build(
+        revoke_accounts(admin, mint, beneficiary, recovery_destination),
+        instruction::RevokeVesting {},
+    )
+}
+
+pub fn revoke_vesting_good_leaver(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    beneficiary_token_account: &Pubkey,
+    recovery_destination: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::RevokeVestingGoodLeaver {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            beneficiary_token_account: *beneficiary_token_account,
+            recovery_destination: *recovery_destination,
+            mint: *mint,
+            token_program: token::ID,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::RevokeVestingGoodLeaver {},
+    )
+}
+
+pub fn terminate_vesting(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    recovery_destination: &Pubkey,
+    end_time: i64,
+) -> Instruction {
+    build(
+        revoke_accounts(admin, mint, beneficiary, recovery_destination),
+        instruction::TerminateVesting { end_time },
+    )
+}
+
+pub fn reduce_grant(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    recovery_destination: &Pubkey,
+    new_total: u64,
+) -> Instruction {
+    build(
+        revoke_accounts(admin, mint, beneficiary, recovery_destination),
+        instruction::ReduceGrant { new_total },
+    )
+}
+
+/// `source_token_account` is the admin's token account that funds the top-up.
+pub fn amend_vesting(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    source_token_account: &Pubkey,
+    new_total: u64,
+    new_duration: i64,
+) -> Instruction {
+    build(
+        accounts::AmendVesting {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            source_token_account: *source_token_account,
+            mint: *mint,
+            admin: *admin,
+            token_program: token::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::AmendVesting {
+            new_total,
+            new_duration,
+        },
+    )
+}
+
+pub fn split_vesting(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    new_beneficiary: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::SplitVesting {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            new_vesting_account: pda::vesting(mint, new_beneficiary).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            payer: *payer,
+            admin: *admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::SplitVesting {
+            amount,
+            new_beneficiary: *new_beneficiary,
+        },
+    )
+}
+
+/// Merges `sources` into the beneficiary's canonical vesting account, closing them to the beneficiary.
+pub fn merge_vesting(admin: &Pubkey, mint: &Pubkey, beneficiary: &Pubkey, sources: &[Pubkey]) -> Instruction {
+    with_remaining(
+        build(
+            accounts::MergeVesting {
+                vesting_account: pda::vesting(mint, beneficiary).0,
+                vault: pda::vault(mint).0,
+                mint: *mint,
+                beneficiary: *beneficiary,
+                admin: *admin,
+                #[cfg(feature = "event-cpi")]
+                event_authority: pda::event_authority().0,
+                #[cfg(feature = "event-cpi")]
+                program: shorthusk_vesting::ID,
+            },
+            instruction::MergeVesting {},
+        ),
+        sources.iter().map(|source| AccountMeta::new(*source, false)),
+    )
+}
+
+pub fn instant_unlock(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    beneficiary_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::InstantUnlock {
+            vesting_account: pda::vesting(mint, beneficiary).0,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            beneficiary_token_account: *beneficiary_token_account,
+            mint: *mint,
+            token_program: token::ID,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::InstantUnlock {},
+    )
+}
+
+pub fn emergency_recover(admin: &Pubkey, mint: &Pubkey, recovery_destination: &Pubkey) -> Instruction {
+    build(
+        accounts::EmergencyRecover {
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            recovery_destination: *recovery_destination,
+            mint: *mint,
+            token_program: token::ID,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::EmergencyRecover {},
+    )
+}
+
+pub fn fund_vault_existing(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    source_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::FundVaultExisting {
+            vault: pda::vault(mint).0,
+            source_token_account: *source_token_account,
+            vault_token_account: pda::vault_token_account(mint),
+            mint: *mint,
+            admin: *admin,
+            payer: *payer,
+            token_program: token::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::FundVaultExisting { amount },
+    )
+}
+
+fn batch_accounts(admin: &Pubkey, mint: &Pubkey) -> accounts::BatchInitializeVesting {
+    accounts::BatchInitializeVesting {
+        vault: pda::vault(mint).0,
+        mint: *mint,
+        admin: *admin,
+        system_program: system_program::ID,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: shorthusk_vesting::ID,
+    }
+}
+
+fn batch_pairs(mint: &Pubkey, args: &[BatchVestingArgs]) -> Vec<AccountMeta> {
+    args.iter()
+        .flat_map(|arg| {
+            [
+                AccountMeta::new(pda::vesting(mint, &arg.beneficiary).0, false),
+                AccountMeta::new_readonly(arg.beneficiary, false),
+            ]
+        })
+        .collect()
+}
+
+pub fn batch_initialize_vesting_v2(admin: &Pubkey, mint: &Pubkey, args: Vec<BatchVestingArgs>) -> Instruction {
+    let remaining = batch_pairs(mint, &args);
+    with_remaining(
+        build(batch_accounts(admin, mint), instruction::BatchInitializeVestingV2 { args }),
+        remaining,
+    )
+}
+
+pub fn batch_initialize_vesting_idempotent(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    args: Vec<BatchVestingArgs>,
+) -> Instruction {
+    let remaining = batch_pairs(mint, &args);
+    with_remaining(
+        build(
+            batch_accounts(admin, mint),
+            instruction::BatchInitializeVestingIdempotent { args },
+        ),
+        remaining,
+    )
+}
+
+/// Most entries a compact batch fits in a legacy transaction with a separate fee payer and a
+/// compute-budget instruction. Larger batches need a v0 transaction with an address lookup table.
+pub const MAX_COMPACT_BATCH_SIZE: usize = 10;
+
+/// Builds a compact batch for `(beneficiary, total_amount)` grants sharing `schedule`, filling in the
+/// canonical bump of each vesting PDA.
+pub fn batch_initialize_vesting_compact(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    schedule: Schedule,
+    grants: &[(Pubkey, u64)],
+) -> Instruction {
+    let (vesting_accounts, entries): (Vec<_>, Vec<_>) = grants
+        .iter()
+        .map(|(beneficiary, total_amount)| {
+            let (vesting_account, bump) = pda::vesting(mint, beneficiary);
+            (
+                AccountMeta::new(vesting_account, false),
+                BatchVestingEntry {
+                    beneficiary: *beneficiary,
+                    total_amount: *total_amount,
+                    bump,
+                },
+            )
+        })
+        .unzip();
+    with_remaining(
+        build(
+            batch_accounts(admin, mint),
+            instruction::BatchInitializeVestingCompact {
+                schedule: schedule.into(),
+                entries,
+            },
+        ),
+        vesting_accounts,
+    )
+}
+
+fn batch_admin_action(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    vesting_accounts: &[Pubkey],
+    data: impl InstructionData,
+) -> Instruction {
+    with_remaining(
+        build(
+            accounts::BatchAdminAction {
+                vault: pda::vault(mint).0,
+                mint: *mint,
+                admin: *admin,
+                #[cfg(feature = "event-cpi")]
+                event_authority: pda::event_authority().0,
+                #[cfg(feature = "event-cpi")]
+                program: shorthusk_vesting::ID,
+            },
+            data,
+        ),
+        vesting_accounts.iter().map(|account| AccountMeta::new(*account, false)),
+    )
+}
+
+pub fn batch_pause(admin: &Pubkey, mint: &Pubkey, vesting_accounts: &[Pubkey]) -> Instruction {
+    batch_admin_action(admin, mint, vesting_accounts, instruction::BatchPause {})
+}
+
+pub fn batch_unpause(admin: &Pubkey, mint: &Pubkey, vesting_accounts: &[Pubkey]) -> Instruction {
+    batch_admin_action(admin, mint, vesting_accounts, instruction::BatchUnpause {})
+}
+
+pub fn batch_revoke_vesting(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    recovery_destination: &Pubkey,
+    vesting_accounts: &[Pubkey],
+) -> Instruction {
+    with_remaining(
+        build(
+            accounts::BatchRevokeVesting {
+                vault: pda::vault(mint).0,
+                vault_token_account: pda::vault_token_account(mint),
+                recovery_destination: *recovery_destination,
+                mint: *mint,
+                token_program: token::ID,
+                admin: *admin,
+                #[cfg(feature = "event-cpi")]
+                event_authority: pda::event_authority().0,
+                #[cfg(feature = "event-cpi")]
+                program: shorthusk_vesting::ID,
+            },
+            instruction::BatchRevokeVesting {},
+        ),
+        vesting_accounts.iter().map(|account| AccountMeta::new(*account, false)),
+    )
+}
+
+pub fn initialize_distribution(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    id: u64,
+    root: [u8; 32],
+    total_amount: u64,
+) -> Instruction {
+    build(
+        accounts::InitializeDistribution {
+            distribution: pda::distribution(mint, id).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            payer: *payer,
+            admin: *admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::InitializeDistribution {
+            id,
+            root,
+            total_amount,
+        },
+    )
+}
+
+pub fn claim_merkle(
+    beneficiary: &Pubkey,
+    mint: &Pubkey,
+    distribution_id: u64,
+    beneficiary_token_account: &Pubkey,
+    amount: u64,
+    schedule: Schedule,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let distribution = pda::distribution(mint, distribution_id).0;
+    build(
+        accounts::ClaimMerkle {
+            vesting_account: pda::merkle_vesting(&distribution, beneficiary).0,
+            claim_receipt: pda::merkle_claim_receipt(&distribution, beneficiary).0,
+            distribution,
+            vault: pda::vault(mint).0,
+            vault_token_account: pda::vault_token_account(mint),
+            beneficiary_token_account: *beneficiary_token_account,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::ClaimMerkle {
+            amount,
+            start_time: schedule.start_time,
+            cliff_period: schedule.cliff_period,
+            duration: schedule.duration,
+            proof,
+        },
+    )
+}
+
+/// Closes Merkle distribution `id`, releasing its unallocated remainder and returning its rent to the admin.
+pub fn close_distribution(admin: &Pubkey, mint: &Pubkey, id: u64) -> Instruction {
+    build(
+        accounts::CloseDistribution {
+            distribution: pda::distribution(mint, id).0,
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::CloseDistribution {},
+    )
+}
+
+/// Brings the vault of `mint`, created by an earlier program version, to the current layout.
+pub fn migrate_vault(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateVault {
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            admin: *admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::MigrateVault {},
+    )
+}
+
+/// Brings `vesting_accounts`, grants created by an earlier program version, to the current layout.
+pub fn migrate_vesting(admin: &Pubkey, mint: &Pubkey, vesting_accounts: &[Pubkey]) -> Instruction {
+    with_remaining(
+        build(
+            accounts::MigrateVesting {
+                vault: pda::vault(mint).0,
+                mint: *mint,
+                admin: *admin,
+                system_program: system_program::ID,
+                #[cfg(feature = "event-cpi")]
+                event_authority: pda::event_authority().0,
+                #[cfg(feature = "event-cpi")]
+                program: shorthusk_vesting::ID,
+            },
+            instruction::MigrateVesting {},
+        ),
+        vesting_accounts.iter().map(|account| AccountMeta::new(*account, false)),
+    )
+}
+
+pub fn update_admin(admin: &Pubkey, mint: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::UpdateAdmin {
+            vault: pda::vault(mint).0,
+            mint: *mint,
+            admin: *admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: shorthusk_vesting::ID,
+        },
+        instruction::UpdateAdmin {
+            new_admin: *new_admin,
+        },
+    )
+}
@@ -0,0 +1,178 @@
+//! Rust client helpers for the `shorthusk-vesting` program.
+//!
+//! - [`pda`] derives the vault, vesting, distribution and event authority PDAs and the vault's token account.
+//! - [`instructions`] builds every program instruction from wallets and token accounts.
+//! - [`state`] decodes `Vault`, `VestingAccount` and `MerkleDistribution` account data and view return data.
+//! - [`error`] maps custom program error codes back to `VestingError`.
+//! - [`import`] validates CSV/JSON grant tables and splits them into batch transactions.
+//! - `rpc` (with the `rpc` feature) fetches vesting accounts from a cluster.
+
+pub mod error;
+pub mod import;
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod state;
+
+pub use error::{decode_error, decode_error_message};
+pub use instructions::{ClaimManyGrant, Schedule};
+pub use shorthusk_vesting::{
+    BatchVestingArgs, MerkleDistribution, Vault, VaultSummary, VestingAccount, VestingError, VestingStatus,
+    VestingStatusReason, ID as PROGRAM_ID,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::{AccountSerialize, Discriminator};
+
+    #[test]
+    fn decodes_every_error_code() {
+        for code in 6000.. {
+            let Some(error) = decode_error(code) else {
+                break;
+            };
+            assert_eq!(u32::from(error), code);
+        }
+        assert!(matches!(
+            decode_error(6001),
+            Some(VestingError::NothingToClaim)
+        ));
+        assert!(matches!(
+            decode_error_message("Error processing Instruction 0: custom program error: 0x1771"),
+            Some(VestingError::NothingToClaim)
+        ));
+        assert!(decode_error(5999).is_none());
+    }
+
+    #[test]
+    fn claim_uses_derived_accounts() {
+        let mint = Pubkey::new_unique();
+        let beneficiary = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let ix = instructions::claim(&beneficiary, &mint, &destination);
+
+        assert_eq!(ix.program_id, PROGRAM_ID);
+        assert_eq!(ix.data, shorthusk_vesting::instruction::Claim::DISCRIMINATOR);
+        assert_eq!(ix.accounts[0].pubkey, pda::vesting(&mint, &beneficiary).0);
+        assert_eq!(ix.accounts[1].pubkey, pda::vault(&mint).0);
+        assert_eq!(ix.accounts[2].pubkey, pda::vault_token_account(&mint));
+        assert!(ix.accounts[5].is_signer);
+    }
+
+    #[test]
+    fn max_compact_batch_fits_a_legacy_transaction() {
+        use anchor_lang::solana_program::{instruction::Instruction, message::Message};
+
+        // Solana's `PACKET_DATA_SIZE`: the most bytes a serialized transaction may take.
+        const PACKET_DATA_SIZE: usize = 1232;
+        let mint = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let fee_payer = Pubkey::new_unique();
+        let transaction_size = |entries: usize| {
+            let grants: Vec<_> = (0..entries).map(|_| (Pubkey::new_unique(), 1)).collect();
+            let schedule = Schedule { start_time: 0, cliff_period: 0, duration: 100 };
+            let set_compute_unit_limit = Instruction {
+                program_id: "ComputeBudget111111111111111111111111111111".parse().unwrap(),
+                accounts: vec![],
+                data: vec![2, 0, 0, 0, 0],
+            };
+            let batch = instructions::batch_initialize_vesting_compact(&admin, &mint, schedule, &grants);
+            let message = Message::new(&[set_compute_unit_limit, batch], Some(&fee_payer));
+            1 + 64 * usize::from(message.header.num_required_signatures) + message.serialize().len()
+        };
+
+        assert!(transaction_size(instructions::MAX_COMPACT_BATCH_SIZE) <= PACKET_DATA_SIZE);
+        assert!(transaction_size(instructions::MAX_COMPACT_BATCH_SIZE + 1) > PACKET_DATA_SIZE);
+    }
+
+    #[cfg(feature = "event-cpi")]
+    #[test]
+    fn appends_event_cpi_accounts_before_remaining_accounts() {
+        let mint = Pubkey::new_unique();
+        let vesting_account = Pubkey::new_unique();
+        let ix = instructions::batch_pause(&Pubkey::new_unique(), &mint, &[vesting_account]);
+        let count = ix.accounts.len();
+
+        assert_eq!(ix.accounts[count - 3].pubkey, pda::event_authority().0);
+        assert_eq!(ix.accounts[count - 2].pubkey, PROGRAM_ID);
+        assert_eq!(ix.accounts[count - 1].pubkey, vesting_account);
+    }
+
+    #[test]
+    fn round_trips_vault_state() {
+        let vault = Vault {
+            bump: 254,
+            admin: Pubkey::new_unique(),
+            paused: false,
+            initialized: true,
+            irrevocable_outstanding: 42,
+            total_committed: 100,
+            total_claimed: 10,
+            active_grants: 2,
+            distribution_reserved: 7,
+        };
+        let mut data = Vec::new();
+        vault.try_serialize(&mut data).unwrap();
+
+        let decoded = state::vault(&data).unwrap();
+        assert_eq!(decoded.admin, vault.admin);
+        assert_eq!(decoded.irrevocable_outstanding, 42);
+        assert_eq!(decoded.active_grants, 2);
+        assert_eq!(decoded.distribution_reserved, 7);
+        assert!(state::vesting_account(&data).is_err());
+    }
+
+    #[test]
+    fn decodes_grants_in_the_earlier_layout() {
+        let mut grant = VestingAccount::new(Pubkey::new_unique(), Pubkey::new_unique(), 1_000, 100, 1_000, 300, 255, true);
+        grant.claimed_amount = 100;
+        grant.terminated_at = 1_500;
+        let mut data = Vec::new();
+        grant.try_serialize(&mut data).unwrap();
+
+        // Earlier program versions wrote the same fields, up to and including `bump`.
+        let earlier = &data[..8 + VestingAccount::V1_LEN];
+        assert!(state::vesting_account(earlier).is_err());
+        let migrated = VestingAccount::try_deserialize_v1(earlier).unwrap();
+        assert_eq!((migrated.total_amount, migrated.claimed_amount), (300, 100));
+        assert_eq!(migrated.schedule_amount, 300);
+        assert_eq!(migrated.terminated_at, 0);
+        assert!(!migrated.irrevocable);
+        assert_eq!(migrated.status(1_600, false).unwrap().vested, 180);
+
+        assert!(matches!(
+            VestingAccount::try_deserialize_v1(&data),
+            Err(err) if err == VestingError::AlreadyMigrated.into()
+        ));
+        assert!(VestingAccount::try_deserialize_v1(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn reports_status_without_failing_on_paused_grants() {
+        let mut grant = VestingAccount::new(Pubkey::new_unique(), Pubkey::new_unique(), 1_000, 100, 1_000, 3, 255, false);
+        let before_cliff = grant.status(1_050, false).unwrap();
+        assert_eq!(before_cliff.reason, VestingStatusReason::BeforeCliff);
+        // One token vests every 333.3 seconds, the first only once the cliff has passed.
+        assert_eq!(before_cliff.next_unlock_time, 1_334);
+        assert_eq!(before_cliff.end_time, 2_000);
+
+        grant.paused = true;
+        let paused = grant.status(1_700, false).unwrap();
+        assert_eq!((paused.vested, paused.claimable, paused.locked), (2, 0, 1));
+        assert_eq!(paused.reason, VestingStatusReason::Paused);
+        assert_eq!(paused.next_unlock_time, 2_000);
+
+        grant.paused = false;
+        grant.claimed_amount = 2;
+        assert_eq!(grant.status(1_700, false).unwrap().reason, VestingStatusReason::AwaitingUnlock);
+        let ended = grant.status(2_500, false).unwrap();
+        assert_eq!((ended.claimable, ended.next_unlock_time), (1, 0));
+
+        let mut return_data = Vec::new();
+        anchor_lang::AnchorSerialize::serialize(&ended, &mut return_data).unwrap();
+        assert_eq!(state::vesting_status(&return_data).unwrap(), ended);
+    }
+}
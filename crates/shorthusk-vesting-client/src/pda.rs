@@ -0,0 +1,54 @@
+//! PDA derivation matching the seeds used by the program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+
+/// `[b"vault", mint]`
+pub fn vault(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", mint.as_ref()], &shorthusk_vesting::ID)
+}
+
+/// `[b"vesting", vault, mint, beneficiary]`
+pub fn vesting(mint: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    let (vault, _) = vault(mint);
+    Pubkey::find_program_address(
+        &[b"vesting", vault.as_ref(), mint.as_ref(), beneficiary.as_ref()],
+        &shorthusk_vesting::ID,
+    )
+}
+
+/// `[b"distribution", vault, id]`
+pub fn distribution(mint: &Pubkey, id: u64) -> (Pubkey, u8) {
+    let (vault, _) = vault(mint);
+    Pubkey::find_program_address(
+        &[b"distribution", vault.as_ref(), id.to_le_bytes().as_ref()],
+        &shorthusk_vesting::ID,
+    )
+}
+
+/// `[b"vesting", distribution, beneficiary]`, created by `claim_merkle`.
+pub fn merkle_vesting(distribution: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vesting", distribution.as_ref(), beneficiary.as_ref()],
+        &shorthusk_vesting::ID,
+    )
+}
+
+/// `[b"merkle_receipt", distribution, beneficiary]`, marks the beneficiary's leaf as claimed.
+pub fn merkle_claim_receipt(distribution: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"merkle_receipt", distribution.as_ref(), beneficiary.as_ref()],
+        &shorthusk_vesting::ID,
+    )
+}
+
+/// `[b"__event_authority"]`, which signs the self-CPIs that carry events when the program is built with
+/// `event-cpi`.
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &shorthusk_vesting::ID)
+}
+
+/// The vault's associated token account, which holds the tokens it pays out.
+pub fn vault_token_account(mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&vault(mint).0, mint)
+}
@@ -0,0 +1,44 @@
+//! Queries against a cluster, enabled with the `rpc` feature.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use shorthusk_vesting::VestingAccount;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+
+use crate::state::{self, VESTING_MINT_OFFSET};
+
+/// Fetches every vesting account of `mint` with `getProgramAccounts`, filtering on the account discriminator
+/// and the mint. Fails on the first account that does not decode, such as a grant created by an earlier
+/// program version that has not gone through `migrate_vesting` yet, so that reports never leave grants out.
+pub fn vesting_accounts(rpc: &RpcClient, mint: &Pubkey) -> Result<Vec<(Pubkey, VestingAccount)>, Box<ClientError>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, VestingAccount::DISCRIMINATOR.to_vec())),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(VESTING_MINT_OFFSET, mint.to_bytes().to_vec())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = rpc
+        .get_program_accounts_with_config(&shorthusk_vesting::ID, config)
+        .map_err(Box::new)?;
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let vesting = state::vesting_account(&account.data).map_err(|err| {
+                Box::new(ClientError::from(ClientErrorKind::Custom(format!(
+                    "vesting account {address} does not decode ({err}); grants created by an earlier program \
+                     version must be migrated with migrate_vesting"
+                ))))
+            })?;
+            Ok((address, vesting))
+        })
+        .collect()
+}
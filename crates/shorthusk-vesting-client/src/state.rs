@@ -0,0 +1,32 @@
+//! Decoding of the program's accounts from raw account data, and of view instructions' return data.
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Result};
+use shorthusk_vesting::{MerkleDistribution, Vault, VaultSummary, VestingAccount, VestingStatus};
+
+/// Byte offset of `VestingAccount::mint` in account data: the discriminator, then `beneficiary`.
+pub const VESTING_MINT_OFFSET: usize = 8 + 32;
+
+/// Decodes a `Vault`, checking its discriminator.
+pub fn vault(data: &[u8]) -> Result<Vault> {
+    Vault::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `VestingAccount`, checking its discriminator.
+pub fn vesting_account(data: &[u8]) -> Result<VestingAccount> {
+    VestingAccount::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `MerkleDistribution`, checking its discriminator.
+pub fn distribution(data: &[u8]) -> Result<MerkleDistribution> {
+    MerkleDistribution::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the return data of `get_vesting_status`.
+pub fn vesting_status(return_data: &[u8]) -> std::io::Result<VestingStatus> {
+    VestingStatus::try_from_slice(return_data)
+}
+
+/// Decodes the return data of `get_vault_summary`.
+pub fn vault_summary(return_data: &[u8]) -> std::io::Result<VaultSummary> {
+    VaultSummary::try_from_slice(return_data)
+}
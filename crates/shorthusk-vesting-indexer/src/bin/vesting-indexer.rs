@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use shorthusk_vesting_indexer::store::Store;
+use shorthusk_vesting_indexer::{decode_cpi_event, parse_logs, VestingEvent};
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::bs58;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction, UiLoadedAddresses,
+    UiMessage, UiTransactionEncoding,
+};
+
+/// Indexes shorthusk-vesting events into a SQLite database, resuming after the last indexed transaction.
+#[derive(Parser)]
+#[command(name = "vesting-indexer", version)]
+struct Cli {
+    /// RPC endpoint. Needs transaction history, so the node must not prune it.
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    #[arg(long, default_value = "vesting-events.sqlite")]
+    db: PathBuf,
+    /// Keeps polling for new transactions every this many seconds instead of exiting once caught up.
+    #[arg(long)]
+    poll: Option<u64>,
+}
+
+/// Events of a transaction: those in its log messages followed by those carried in self-CPIs. A given build
+/// of the program only uses one of the two, depending on its `event-cpi` feature.
+fn transaction_events(transaction: &EncodedTransactionWithStatusMeta) -> Vec<VestingEvent> {
+    let Some(meta) = &transaction.meta else {
+        return Vec::new();
+    };
+    let logs: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+    let mut events = parse_logs(&logs);
+
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction else {
+        return events;
+    };
+    let UiMessage::Raw(message) = &ui_transaction.message else {
+        return events;
+    };
+    // Inner instructions index into the static keys followed by those loaded from lookup tables.
+    let loaded: Option<UiLoadedAddresses> = Option::from(meta.loaded_addresses.clone());
+    let keys: Vec<&String> = message
+        .account_keys
+        .iter()
+        .chain(loaded.iter().flat_map(|loaded| loaded.writable.iter().chain(&loaded.readonly)))
+        .collect();
+    let program_id = shorthusk_vesting::ID.to_string();
+    let inner: Vec<UiInnerInstructions> = Option::from(meta.inner_instructions.clone()).unwrap_or_default();
+    for instruction in inner.iter().flat_map(|inner| &inner.instructions) {
+        let UiInstruction::Compiled(instruction) = instruction else {
+            continue;
+        };
+        if keys.get(instruction.program_id_index as usize) != Some(&&program_id) {
+            continue;
+        }
+        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
+            events.extend(decode_cpi_event(&data));
+        }
+    }
+    events
+}
+
+/// Indexes every program transaction newer than the latest one in `store`, oldest first, and returns
+/// how many were indexed.
+fn catch_up(rpc: &RpcClient, store: &mut Store) -> Result<usize> {
+    let until = store
+        .latest_signature()?
+        .map(|signature| Signature::from_str(&signature))
+        .transpose()?;
+    let mut pending = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            &shorthusk_vesting::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        pending.extend(page);
+    }
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    for status in pending.iter().rev() {
+        // Failed transactions roll back their state but still log events, so only their position is kept.
+        let events = match status.err {
+            Some(_) => Vec::new(),
+            None => {
+                let signature = Signature::from_str(&status.signature)?;
+                let transaction = rpc
+                    .get_transaction_with_config(&signature, config)
+                    .with_context(|| format!("fetching {signature}"))?;
+                transaction_events(&transaction.transaction)
+            }
+        };
+        store.insert_transaction(&status.signature, status.slot, status.block_time, &events)?;
+    }
+    Ok(pending.len())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.url);
+    let mut store = Store::open(&cli.db).with_context(|| format!("opening {}", cli.db.display()))?;
+    loop {
+        let indexed = catch_up(&rpc, &mut store)?;
+        eprintln!("Indexed {indexed} transactions into {}", cli.db.display());
+        match cli.poll {
+            Some(seconds) => thread::sleep(Duration::from_secs(seconds)),
+            None => return Ok(()),
+        }
+    }
+}
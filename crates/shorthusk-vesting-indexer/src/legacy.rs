@@ -0,0 +1,209 @@
+//! Event layouts of program versions from before events carried `timestamp`, `slot` and the resulting grant
+//! and vault state.
+//!
+//! Those versions logged the same event names, so their data starts with the same discriminators and only
+//! its length tells the layouts apart. [`VestingEvent::decode`](crate::VestingEvent::decode) falls back to
+//! [`LegacyEvent::decode`] when the current layout does not consume the data exactly, which keeps rows
+//! indexed before the layout change readable.
+
+use anchor_lang::prelude::{borsh, Pubkey};
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator};
+use serde_json::{Map, Value};
+use shorthusk_vesting::{
+    BatchInitializeVestingEvent, BatchItemResultEvent, BatchItemStatus, ClaimEvent, DistributionInitializedEvent,
+    EmergencyRecoverEvent, FundVaultExistingEvent, InstantUnlockEvent, MerkleVestingCreatedEvent, PauseEvent,
+    PauseVaultEvent, ReduceGrantEvent, RevokeVestingEvent, TerminateVestingEvent, UnpauseEvent, UnpauseVaultEvent,
+    UpdateAdminEvent, VaultInitializedEvent, VestingAmendedEvent, VestingInitializedEvent, VestingMergedEvent,
+    VestingReinitializedEvent, VestingSplitEvent,
+};
+
+use crate::Field;
+
+macro_rules! legacy_events {
+    ($($variant:ident($event:ident as $current:ident { $($field:ident: $ty:ty),* $(,)? }),)*) => {
+        $(
+            #[doc = concat!("Earlier layout of `", stringify!($current), "`.")]
+            #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+            pub struct $event {
+                $(pub $field: $ty,)*
+            }
+        )*
+
+        /// An event in the layout of an earlier program version.
+        pub enum LegacyEvent {
+            $($variant($event),)*
+        }
+
+        impl LegacyEvent {
+            /// Decodes discriminator-prefixed event data in an earlier layout, or returns `None` if it is not
+            /// one.
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(mut body) = data.strip_prefix(<$current as Discriminator>::DISCRIMINATOR) {
+                        let event = <$event as AnchorDeserialize>::deserialize(&mut body).ok()?;
+                        return body.is_empty().then_some(Self::$variant(event));
+                    }
+                )*
+                None
+            }
+
+            /// Discriminator-prefixed Borsh encoding, as logged by the earlier program version.
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $(Self::$variant(event) => {
+                        let mut data = <$current as Discriminator>::DISCRIMINATOR.to_vec();
+                        event.serialize(&mut data).expect("writing to a Vec cannot fail");
+                        data
+                    })*
+                }
+            }
+
+            /// Name of the event struct the program logged, e.g. `ClaimEvent`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($current),)*
+                }
+            }
+
+            /// Mint of the vault the event belongs to.
+            pub fn mint(&self) -> Pubkey {
+                match self {
+                    $(Self::$variant(event) => event.mint,)*
+                }
+            }
+
+            /// The fields the earlier layout has, as a JSON object.
+            pub fn to_json(&self) -> Value {
+                let mut fields = Map::new();
+                match self {
+                    $(Self::$variant(event) => {
+                        $(fields.insert(stringify!($field).to_string(), event.$field.to_json());)*
+                    })*
+                }
+                Value::Object(fields)
+            }
+        }
+    };
+}
+
+legacy_events! {
+    Claim(ClaimEventV1 as ClaimEvent { vesting_account: Pubkey, beneficiary: Pubkey, amount: u64, mint: Pubkey }),
+    Pause(PauseEventV1 as PauseEvent { vesting_account: Pubkey, admin: Pubkey, mint: Pubkey }),
+    Unpause(UnpauseEventV1 as UnpauseEvent { vesting_account: Pubkey, admin: Pubkey, mint: Pubkey }),
+    RevokeVesting(RevokeVestingEventV1 as RevokeVestingEvent {
+        vesting_account: Pubkey,
+        admin: Pubkey,
+        remaining_amount: u64,
+        beneficiary_amount: u64,
+        mint: Pubkey,
+    }),
+    TerminateVesting(TerminateVestingEventV1 as TerminateVestingEvent {
+        vesting_account: Pubkey,
+        admin: Pubkey,
+        end_time: i64,
+        vested_amount: u64,
+        remaining_amount: u64,
+        mint: Pubkey,
+    }),
+    ReduceGrant(ReduceGrantEventV1 as ReduceGrantEvent {
+        vesting_account: Pubkey,
+        admin: Pubkey,
+        old_total: u64,
+        new_total: u64,
+        mint: Pubkey,
+    }),
+    VestingAmended(VestingAmendedEventV1 as VestingAmendedEvent {
+        vesting_account: Pubkey,
+        admin: Pubkey,
+        old_total: u64,
+        new_total: u64,
+        old_duration: i64,
+        new_duration: i64,
+        mint: Pubkey,
+    }),
+    VestingSplit(VestingSplitEventV1 as VestingSplitEvent {
+        vesting_account: Pubkey,
+        new_vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        new_beneficiary: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    }),
+    VestingMerged(VestingMergedEventV1 as VestingMergedEvent {
+        vesting_account: Pubkey,
+        merged_account: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    }),
+    InstantUnlock(InstantUnlockEventV1 as InstantUnlockEvent {
+        vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        admin: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    }),
+    EmergencyRecover(EmergencyRecoverEventV1 as EmergencyRecoverEvent {
+        vault: Pubkey,
+        admin: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    }),
+    FundVaultExisting(FundVaultExistingEventV1 as FundVaultExistingEvent {
+        vault: Pubkey,
+        admin: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    }),
+    UpdateAdmin(UpdateAdminEventV1 as UpdateAdminEvent {
+        vault: Pubkey,
+        old_admin: Pubkey,
+        new_admin: Pubkey,
+        mint: Pubkey,
+    }),
+    PauseVault(PauseVaultEventV1 as PauseVaultEvent { vault: Pubkey, admin: Pubkey, mint: Pubkey }),
+    UnpauseVault(UnpauseVaultEventV1 as UnpauseVaultEvent { vault: Pubkey, admin: Pubkey, mint: Pubkey }),
+    BatchInitializeVesting(BatchInitializeVestingEventV1 as BatchInitializeVestingEvent {
+        vault: Pubkey,
+        admin: Pubkey,
+        num_accounts: u64,
+        vesting_account: Pubkey,
+        mint: Pubkey,
+    }),
+    DistributionInitialized(DistributionInitializedEventV1 as DistributionInitializedEvent {
+        distribution: Pubkey,
+        vault: Pubkey,
+        admin: Pubkey,
+        root: [u8; 32],
+        total_amount: u64,
+        mint: Pubkey,
+    }),
+    MerkleVestingCreated(MerkleVestingCreatedEventV1 as MerkleVestingCreatedEvent {
+        distribution: Pubkey,
+        vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        total_amount: u64,
+        mint: Pubkey,
+    }),
+    BatchItemResult(BatchItemResultEventV1 as BatchItemResultEvent {
+        vault: Pubkey,
+        index: u32,
+        vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        status: BatchItemStatus,
+        mint: Pubkey,
+    }),
+    VaultInitialized(VaultInitializedEventV1 as VaultInitializedEvent { vault: Pubkey, admin: Pubkey, mint: Pubkey }),
+    VestingInitialized(VestingInitializedEventV1 as VestingInitializedEvent {
+        vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        mint: Pubkey,
+        total_amount: u64,
+        irrevocable: bool,
+    }),
+    VestingReinitialized(VestingReinitializedEventV1 as VestingReinitializedEvent {
+        vesting_account: Pubkey,
+        beneficiary: Pubkey,
+        mint: Pubkey,
+        total_amount: u64,
+    }),
+}
@@ -0,0 +1,466 @@
+//! Typed decoding of `shorthusk-vesting` events and a SQLite index of them.
+//!
+//! `emit!` logs each event as a `Program data: <base64>` line holding the event's discriminator followed by
+//! its Borsh encoding. [`parse_logs`] walks a transaction's log messages, keeps only the lines logged while
+//! the vesting program itself is executing, and decodes them into [`VestingEvent`]s. A program built with
+//! its `event-cpi` feature instead carries each event in a self-CPI, whose instruction data
+//! [`decode_cpi_event`] decodes. [`store`] persists decoded events keyed by signature and position in the
+//! transaction. Events logged by earlier program versions decode to [`VestingEvent::Legacy`].
+
+pub mod legacy;
+pub mod store;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use serde_json::{Map, Value};
+use legacy::LegacyEvent;
+use shorthusk_vesting::{
+    BatchInitializeVestingEvent, BatchItemResultEvent, BatchItemStatus, ClaimEvent, DistributionClosedEvent,
+    DistributionInitializedEvent,
+    EmergencyRecoverEvent, FundVaultExistingEvent, InstantUnlockEvent, MerkleVestingCreatedEvent, PauseEvent,
+    PauseVaultEvent, ReduceGrantEvent, RevokeVestingEvent, TerminateVestingEvent, UnpauseEvent, UnpauseVaultEvent,
+    UpdateAdminEvent, VaultInitializedEvent, VaultMigratedEvent, VestingAmendedEvent, VestingInitializedEvent, VestingMergedEvent,
+    VestingMigratedEvent, VestingReinitializedEvent, VestingSplitEvent, ID,
+};
+
+/// Conversion of event field types to JSON.
+trait Field {
+    fn to_json(&self) -> Value;
+}
+
+impl Field for Pubkey {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl Field for u64 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl Field for u32 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl Field for i64 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl Field for bool {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl Field for [u8; 32] {
+    fn to_json(&self) -> Value {
+        Value::String(self.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+}
+
+impl Field for BatchItemStatus {
+    fn to_json(&self) -> Value {
+        Value::String(format!("{self:?}"))
+    }
+}
+
+macro_rules! events {
+    ($($variant:ident($event:ident { $($field:ident),* $(,)? }),)*) => {
+        /// An event emitted by the program.
+        pub enum VestingEvent {
+            $($variant($event),)*
+            /// An event in the layout of an earlier program version.
+            Legacy(LegacyEvent),
+        }
+
+        impl VestingEvent {
+            /// Decodes discriminator-prefixed event data, or returns `None` if it is not a known event. Data that
+            /// does not fit the current layout exactly is decoded as a [`LegacyEvent`].
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(mut body) = data.strip_prefix(<$event as Discriminator>::DISCRIMINATOR) {
+                        return match <$event as AnchorDeserialize>::deserialize(&mut body) {
+                            Ok(event) if body.is_empty() => Some(Self::$variant(event)),
+                            _ => LegacyEvent::decode(data).map(Self::Legacy),
+                        };
+                    }
+                )*
+                None
+            }
+
+            /// Discriminator-prefixed Borsh encoding, as logged by the program.
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $(Self::$variant(event) => anchor_lang::Event::data(event),)*
+                    Self::Legacy(event) => event.data(),
+                }
+            }
+
+            /// Name of the event struct, e.g. `ClaimEvent`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($event),)*
+                    Self::Legacy(event) => event.name(),
+                }
+            }
+
+            /// Mint of the vault the event belongs to.
+            pub fn mint(&self) -> Pubkey {
+                match self {
+                    $(Self::$variant(event) => event.mint,)*
+                    Self::Legacy(event) => event.mint(),
+                }
+            }
+
+            /// The event's fields as a JSON object. Pubkeys are base58 and merkle roots hex encoded.
+            pub fn to_json(&self) -> Value {
+                let mut fields = Map::new();
+                match self {
+                    $(Self::$variant(event) => {
+                        $(fields.insert(stringify!($field).to_string(), event.$field.to_json());)*
+                    })*
+                    Self::Legacy(event) => return event.to_json(),
+                }
+                Value::Object(fields)
+            }
+        }
+    };
+}
+
+events! {
+    Claim(ClaimEvent {
+        vesting_account,
+        beneficiary,
+        amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    Pause(PauseEvent { vesting_account, admin, mint, claimed_amount, total_amount, timestamp, slot }),
+    Unpause(UnpauseEvent { vesting_account, admin, mint, claimed_amount, total_amount, timestamp, slot }),
+    RevokeVesting(RevokeVestingEvent {
+        vesting_account,
+        beneficiary,
+        admin,
+        remaining_amount,
+        beneficiary_amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    TerminateVesting(TerminateVestingEvent {
+        vesting_account,
+        admin,
+        end_time,
+        vested_amount,
+        remaining_amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    ReduceGrant(ReduceGrantEvent {
+        vesting_account,
+        admin,
+        old_total,
+        new_total,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    VestingAmended(VestingAmendedEvent {
+        vesting_account,
+        admin,
+        old_total,
+        new_total,
+        old_duration,
+        new_duration,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    VestingSplit(VestingSplitEvent {
+        vesting_account,
+        new_vesting_account,
+        beneficiary,
+        new_beneficiary,
+        amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        new_claimed_amount,
+        timestamp,
+        slot,
+    }),
+    VestingMerged(VestingMergedEvent {
+        vesting_account,
+        merged_account,
+        amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        timestamp,
+        slot,
+    }),
+    InstantUnlock(InstantUnlockEvent {
+        vesting_account,
+        beneficiary,
+        admin,
+        amount,
+        mint,
+        claimed_amount,
+        total_amount,
+        vault_balance,
+        timestamp,
+        slot,
+    }),
+    EmergencyRecover(EmergencyRecoverEvent { vault, admin, amount, mint, vault_balance, timestamp, slot }),
+    FundVaultExisting(FundVaultExistingEvent { vault, admin, amount, mint, vault_balance, timestamp, slot }),
+    UpdateAdmin(UpdateAdminEvent { vault, old_admin, new_admin, mint, timestamp, slot }),
+    PauseVault(PauseVaultEvent { vault, admin, mint, timestamp, slot }),
+    UnpauseVault(UnpauseVaultEvent { vault, admin, mint, timestamp, slot }),
+    BatchInitializeVesting(BatchInitializeVestingEvent {
+        vault,
+        admin,
+        num_accounts,
+        vesting_account,
+        mint,
+        total_amount,
+        timestamp,
+        slot,
+    }),
+    DistributionInitialized(DistributionInitializedEvent {
+        distribution,
+        vault,
+        admin,
+        root,
+        total_amount,
+        mint,
+        timestamp,
+        slot,
+    }),
+    DistributionClosed(DistributionClosedEvent {
+        distribution,
+        vault,
+        admin,
+        released_amount,
+        num_grants,
+        mint,
+        timestamp,
+        slot,
+    }),
+    MerkleVestingCreated(MerkleVestingCreatedEvent {
+        distribution,
+        vesting_account,
+        beneficiary,
+        total_amount,
+        mint,
+        timestamp,
+        slot,
+    }),
+    BatchItemResult(BatchItemResultEvent {
+        vault,
+        index,
+        vesting_account,
+        beneficiary,
+        status,
+        mint,
+        claimed_amount,
+        total_amount,
+        timestamp,
+        slot,
+    }),
+    VaultInitialized(VaultInitializedEvent { vault, admin, mint, timestamp, slot }),
+    VestingInitialized(VestingInitializedEvent {
+        vesting_account,
+        beneficiary,
+        mint,
+        total_amount,
+        irrevocable,
+        timestamp,
+        slot,
+    }),
+    VestingReinitialized(VestingReinitializedEvent { vesting_account, beneficiary, mint, total_amount, timestamp, slot }),
+    VaultMigrated(VaultMigratedEvent { vault, admin, mint, timestamp, slot }),
+    VestingMigrated(VestingMigratedEvent {
+        vesting_account,
+        beneficiary,
+        admin,
+        mint,
+        claimed_amount,
+        total_amount,
+        timestamp,
+        slot,
+    }),
+}
+
+/// Decodes the data of an inner instruction the program invoked on itself to emit an event: Anchor's
+/// `EVENT_IX_TAG_LE` followed by the discriminator-prefixed event. Returns `None` for any other data.
+pub fn decode_cpi_event(data: &[u8]) -> Option<VestingEvent> {
+    VestingEvent::decode(data.strip_prefix(anchor_lang::event::EVENT_IX_TAG_LE)?)
+}
+
+/// Decodes the program's events from a transaction's log messages, in emission order.
+///
+/// Invocations are tracked through the runtime's `invoke`/`success`/`failed` lines so that data logged by
+/// other programs, including ones the vesting program calls into, is ignored. Data that does not decode to
+/// a known event is skipped.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<VestingEvent> {
+    let program_id = ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let Ok(data) = base64::engine::general_purpose::STANDARD.decode(data) else {
+                continue;
+            };
+            events.extend(VestingEvent::decode(&data));
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => stack.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(event: &VestingEvent) -> String {
+        format!(
+            "Program data: {}",
+            base64::engine::general_purpose::STANDARD.encode(event.data())
+        )
+    }
+
+    fn claim(amount: u64) -> VestingEvent {
+        VestingEvent::Claim(ClaimEvent {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            amount,
+            mint: Pubkey::new_unique(),
+            claimed_amount: amount,
+            total_amount: 10,
+            vault_balance: 10 - amount,
+            timestamp: 1_700_000_000,
+            slot: 42,
+        })
+    }
+
+    #[test]
+    fn decodes_only_the_programs_own_events() {
+        let other = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {ID} invoke [1]"),
+            "Program log: Instruction: Claim".to_string(),
+            format!("Program {other} invoke [2]"),
+            log(&claim(1)),
+            format!("Program {other} success"),
+            log(&claim(2)),
+            "Program data: bm90IGFuIGV2ZW50".to_string(),
+            format!("Program {ID} consumed 12345 of 200000 compute units"),
+            format!("Program {ID} success"),
+            format!("Program {other} invoke [1]"),
+            log(&claim(3)),
+            format!("Program {other} success"),
+        ];
+        let events = parse_logs(&logs);
+
+        assert_eq!(events.len(), 1);
+        let VestingEvent::Claim(event) = &events[0] else {
+            panic!("expected a claim, got {}", events[0].name());
+        };
+        assert_eq!(event.amount, 2);
+    }
+
+    #[test]
+    fn serializes_fields_to_json() {
+        let event = VestingEvent::BatchItemResult(BatchItemResultEvent {
+            vault: Pubkey::new_unique(),
+            index: 2,
+            vesting_account: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            status: BatchItemStatus::Skipped,
+            mint: Pubkey::new_unique(),
+            claimed_amount: 0,
+            total_amount: 1_000,
+            timestamp: 1_700_000_000,
+            slot: 42,
+        });
+        let json = event.to_json();
+
+        assert_eq!(event.name(), "BatchItemResultEvent");
+        assert_eq!(json["index"], 2);
+        assert_eq!(json["status"], "Skipped");
+        assert_eq!(json["timestamp"], 1_700_000_000);
+        assert_eq!(json["slot"], 42);
+        assert_eq!(json["mint"], event.mint().to_string());
+        assert!(VestingEvent::decode(&event.data()).is_some());
+    }
+
+    #[test]
+    fn decodes_earlier_layouts_by_length() {
+        let legacy = legacy::InstantUnlockEventV1 {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            amount: 3,
+            mint: Pubkey::new_unique(),
+        };
+        let data = VestingEvent::Legacy(LegacyEvent::InstantUnlock(legacy.clone())).data();
+        let Some(decoded) = VestingEvent::decode(&data) else {
+            panic!("expected an instant unlock");
+        };
+
+        assert_eq!(decoded.name(), "InstantUnlockEvent");
+        assert_eq!(decoded.mint(), legacy.mint);
+        assert_eq!(decoded.to_json()["amount"], 3);
+        assert!(decoded.to_json().get("timestamp").is_none());
+        assert!(matches!(decoded, VestingEvent::Legacy(LegacyEvent::InstantUnlock(_))));
+        assert!(matches!(VestingEvent::decode(&claim(1).data()), Some(VestingEvent::Claim(_))));
+        assert!(VestingEvent::decode(&[&data[..], &[0]].concat()).is_none());
+    }
+
+    #[test]
+    fn decodes_self_cpi_event_data() {
+        let event = claim(4);
+        let ix_data = [anchor_lang::event::EVENT_IX_TAG_LE, &event.data()].concat();
+
+        let Some(VestingEvent::Claim(decoded)) = decode_cpi_event(&ix_data) else {
+            panic!("expected a claim");
+        };
+        assert_eq!(decoded.amount, 4);
+        assert!(decode_cpi_event(&event.data()).is_none());
+    }
+}
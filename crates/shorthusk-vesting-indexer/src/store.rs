@@ -0,0 +1,228 @@
+//! SQLite storage for decoded events.
+//!
+//! Every indexed transaction gets a row in `transactions`, whether or not it emitted events, so the newest
+//! one serves as the resume point for the next run. Events go to `events`, keyed by signature and their
+//! position in the transaction, with the raw event data next to a JSON rendering of its fields for ad-hoc
+//! queries via `json_extract`.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::VestingEvent;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER
+);
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    name TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    data BLOB NOT NULL,
+    fields TEXT NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+CREATE INDEX IF NOT EXISTS events_by_mint ON events (mint, name, slot);
+";
+
+/// An event together with the transaction that emitted it.
+pub struct IndexedEvent {
+    pub signature: String,
+    pub slot: u64,
+    /// Unix timestamp of the block, if the node reported one.
+    pub block_time: Option<i64>,
+    /// Position among the program's events in the transaction.
+    pub log_index: u32,
+    pub event: VestingEvent,
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Opens or creates the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Signature of the most recently indexed transaction.
+    pub fn latest_signature(&self) -> rusqlite::Result<Option<String>> {
+        self.connection
+            .query_row(
+                "SELECT signature FROM transactions ORDER BY rowid DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// Records a transaction and its events atomically. Indexing the same transaction again is a no-op.
+    pub fn insert_transaction(
+        &mut self,
+        signature: &str,
+        slot: u64,
+        block_time: Option<i64>,
+        events: &[VestingEvent],
+    ) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time) VALUES (?1, ?2, ?3)",
+            params![signature, slot, block_time],
+        )?;
+        for (log_index, event) in events.iter().enumerate() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO events (signature, log_index, slot, block_time, name, mint, data, fields)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    log_index as u32,
+                    slot,
+                    block_time,
+                    event.name(),
+                    event.mint().to_string(),
+                    event.data(),
+                    event.to_json().to_string(),
+                ],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Indexed events in chain order, optionally only those of one event type (e.g. `ClaimEvent`) and mint.
+    /// Events in an earlier layout come back as [`VestingEvent::Legacy`]; data no layout decodes is an error.
+    pub fn events(&self, name: Option<&str>, mint: Option<&Pubkey>) -> rusqlite::Result<Vec<IndexedEvent>> {
+        let mut statement = self.connection.prepare(
+            "SELECT events.signature, events.slot, events.block_time, events.log_index, events.data
+             FROM events JOIN transactions USING (signature)
+             WHERE (?1 IS NULL OR name = ?1) AND (?2 IS NULL OR mint = ?2)
+             ORDER BY transactions.rowid, events.log_index",
+        )?;
+        let rows = statement.query_map(params![name, mint.map(Pubkey::to_string)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u64>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, u32>(3)?,
+                row.get::<_, Vec<u8>>(4)?,
+            ))
+        })?;
+        let mut events = Vec::new();
+        for row in rows {
+            let (signature, slot, block_time, log_index, data) = row?;
+            // Rows were written from decoded events, so only data from a newer program can fail here. Dropping
+            // it would silently leave payouts out of statements, so fail instead.
+            let event = VestingEvent::decode(&data).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    4,
+                    rusqlite::types::Type::Blob,
+                    format!("event {log_index} of {signature} does not decode; upgrade the indexer").into(),
+                )
+            })?;
+            events.push(IndexedEvent {
+                signature,
+                slot,
+                block_time,
+                log_index,
+                event,
+            });
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::{ClaimEventV1, LegacyEvent};
+    use shorthusk_vesting::{ClaimEvent, PauseEvent};
+
+    #[test]
+    fn stores_events_once_and_filters_them() {
+        let mint = Pubkey::new_unique();
+        let claim = |amount| {
+            VestingEvent::Claim(ClaimEvent {
+                vesting_account: Pubkey::new_unique(),
+                beneficiary: Pubkey::new_unique(),
+                amount,
+                mint,
+                claimed_amount: amount,
+                total_amount: 1_000,
+                vault_balance: 0,
+                timestamp: 1_700_000_000,
+                slot: 7,
+            })
+        };
+        let pause = VestingEvent::Pause(PauseEvent {
+            vesting_account: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            mint,
+            claimed_amount: 0,
+            total_amount: 1_000,
+            timestamp: 1_700_000_000,
+            slot: 7,
+        });
+        let mut store = Store::open_in_memory().unwrap();
+        assert_eq!(store.latest_signature().unwrap(), None);
+
+        store.insert_transaction("a", 10, Some(1_700_000_000), &[claim(5), pause]).unwrap();
+        store.insert_transaction("b", 11, None, &[]).unwrap();
+        store.insert_transaction("c", 12, None, &[claim(7)]).unwrap();
+        store.insert_transaction("a", 10, Some(1_700_000_000), &[claim(5)]).unwrap();
+
+        assert_eq!(store.latest_signature().unwrap().as_deref(), Some("c"));
+        assert_eq!(store.events(None, None).unwrap().len(), 3);
+        let claims = store.events(Some("ClaimEvent"), Some(&mint)).unwrap();
+        let amounts: Vec<u64> = claims
+            .iter()
+            .map(|indexed| match &indexed.event {
+                VestingEvent::Claim(event) => event.amount,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(amounts, [5, 7]);
+        assert_eq!(claims[0].block_time, Some(1_700_000_000));
+        assert!(store.events(None, Some(&Pubkey::new_unique())).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_earlier_layouts_and_rejects_unknown_data() {
+        let mint = Pubkey::new_unique();
+        let legacy = VestingEvent::Legacy(LegacyEvent::Claim(ClaimEventV1 {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            amount: 9,
+            mint,
+        }));
+        let mut store = Store::open_in_memory().unwrap();
+        store.insert_transaction("a", 10, None, &[legacy]).unwrap();
+
+        let claims = store.events(Some("ClaimEvent"), Some(&mint)).unwrap();
+        let [indexed] = &claims[..] else {
+            panic!("expected one claim");
+        };
+        assert!(matches!(&indexed.event, VestingEvent::Legacy(LegacyEvent::Claim(event)) if event.amount == 9));
+
+        store
+            .connection
+            .execute("UPDATE events SET data = x'00' WHERE signature = 'a'", [])
+            .unwrap();
+        assert!(store.events(None, None).is_err());
+    }
+}
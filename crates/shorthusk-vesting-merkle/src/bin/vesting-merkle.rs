@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use shorthusk_vesting_merkle::{decode_hash, read_grants, Distribution};
+
+/// Builds and verifies Merkle trees for shorthusk-vesting distributions.
+#[derive(Parser)]
+#[command(name = "vesting-merkle", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Builds a distribution from a CSV of grants and writes the root and proofs as JSON.
+    Build {
+        /// CSV with the header `beneficiary,amount,start_time,cliff_period,duration`.
+        #[arg(long)]
+        input: PathBuf,
+        /// Output JSON file; printed to stdout when omitted.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Verifies the proofs in a distribution JSON file against its root.
+    Verify {
+        #[arg(long)]
+        distribution: PathBuf,
+        /// Only verify this beneficiary's proof.
+        #[arg(long)]
+        beneficiary: Option<String>,
+        /// Expected root, e.g. as read from the on-chain `MerkleDistribution` account.
+        #[arg(long)]
+        root: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Build { input, output } => {
+            let file = File::open(&input).with_context(|| format!("opening {}", input.display()))?;
+            let grants = read_grants(file)?;
+            let distribution = Distribution::build(&grants)?;
+            match output {
+                Some(path) => {
+                    let file = File::create(&path)
+                        .with_context(|| format!("creating {}", path.display()))?;
+                    serde_json::to_writer_pretty(file, &distribution)?;
+                    eprintln!(
+                        "Built distribution of {} grants ({} tokens), root {}",
+                        distribution.grants.len(),
+                        distribution.total_amount,
+                        distribution.root
+                    );
+                }
+                None => println!("{}", serde_json::to_string_pretty(&distribution)?),
+            }
+        }
+        Command::Verify {
+            distribution,
+            beneficiary,
+            root,
+        } => {
+            let file = File::open(&distribution)
+                .with_context(|| format!("opening {}", distribution.display()))?;
+            let distribution: Distribution = serde_json::from_reader(file)?;
+            if let Some(root) = root {
+                if decode_hash(&root)? != decode_hash(&distribution.root)? {
+                    bail!("distribution root {} does not match {}", distribution.root, root);
+                }
+            }
+            match beneficiary {
+                Some(beneficiary) => {
+                    let key = Pubkey::from_str(&beneficiary)
+                        .with_context(|| format!("invalid beneficiary {beneficiary}"))?;
+                    let grant = distribution
+                        .find(&key)
+                        .with_context(|| format!("{beneficiary} is not part of the distribution"))?;
+                    if !grant.verify(decode_hash(&distribution.root)?)? {
+                        bail!("proof for {beneficiary} does not verify");
+                    }
+                    println!("Proof for {beneficiary} is valid");
+                }
+                None => {
+                    let failures = distribution.verify()?;
+                    if !failures.is_empty() {
+                        bail!("{} proofs do not verify: {}", failures.len(), failures.join(", "));
+                    }
+                    println!("All {} proofs are valid", distribution.grants.len());
+                }
+            }
+        }
+    }
+    Ok(())
+}
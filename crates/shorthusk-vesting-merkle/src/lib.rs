@@ -0,0 +1,256 @@
+//! Off-chain tooling for `shorthusk-vesting` Merkle distributions.
+//!
+//! Builds a distribution tree from a CSV of grants using the exact leaf and node hashing of the
+//! on-chain program, and writes the root and per-beneficiary proofs as JSON for `claim_merkle`.
+
+mod tree;
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use serde::{Deserialize, Serialize};
+use shorthusk_vesting::{merkle_leaf, verify_merkle_proof};
+use thiserror::Error;
+
+pub use tree::MerkleTree;
+
+#[derive(Debug, Error)]
+pub enum MerkleError {
+    #[error("row {row}: invalid beneficiary `{value}`")]
+    InvalidBeneficiary { row: usize, value: String },
+    #[error("row {row}: duration must be positive")]
+    InvalidDuration { row: usize },
+    #[error("row {row}: cliff period must be non-negative and not greater than duration")]
+    InvalidCliffPeriod { row: usize },
+    #[error("row {row}: amount must be positive")]
+    InvalidAmount { row: usize },
+    #[error("row {row}: duplicate beneficiary {beneficiary}")]
+    DuplicateBeneficiary { row: usize, beneficiary: Pubkey },
+    #[error("distribution has no grants")]
+    Empty,
+    #[error("total amount overflows u64")]
+    Overflow,
+    #[error("invalid hex value `{0}`")]
+    InvalidHex(String),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+}
+
+/// One leaf of a distribution: a single beneficiary's grant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grant {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_period: i64,
+    pub duration: i64,
+}
+
+impl Grant {
+    pub fn leaf(&self) -> [u8; 32] {
+        merkle_leaf(
+            &self.beneficiary,
+            self.amount,
+            self.start_time,
+            self.cliff_period,
+            self.duration,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct GrantRow {
+    beneficiary: String,
+    amount: u64,
+    start_time: i64,
+    cliff_period: i64,
+    duration: i64,
+}
+
+/// Parses grants from CSV with the header `beneficiary,amount,start_time,cliff_period,duration`.
+///
+/// Rows are validated against the same rules the program applies in `claim_merkle`. Beneficiaries
+/// must be unique because each one can create only a single vesting account per distribution.
+pub fn read_grants<R: Read>(reader: R) -> Result<Vec<Grant>, MerkleError> {
+    let mut grants = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, row) in csv::Reader::from_reader(reader).deserialize::<GrantRow>().enumerate() {
+        let row_number = i + 1;
+        let row = row?;
+        let beneficiary = Pubkey::from_str(row.beneficiary.trim()).map_err(|_| {
+            MerkleError::InvalidBeneficiary {
+                row: row_number,
+                value: row.beneficiary.clone(),
+            }
+        })?;
+        if row.duration <= 0 {
+            return Err(MerkleError::InvalidDuration { row: row_number });
+        }
+        if row.cliff_period < 0 || row.cliff_period > row.duration {
+            return Err(MerkleError::InvalidCliffPeriod { row: row_number });
+        }
+        if row.amount == 0 {
+            return Err(MerkleError::InvalidAmount { row: row_number });
+        }
+        if !seen.insert(beneficiary) {
+            return Err(MerkleError::DuplicateBeneficiary {
+                row: row_number,
+                beneficiary,
+            });
+        }
+        grants.push(Grant {
+            beneficiary,
+            amount: row.amount,
+            start_time: row.start_time,
+            cliff_period: row.cliff_period,
+            duration: row.duration,
+        });
+    }
+    Ok(grants)
+}
+
+/// A grant together with its proof, as written to the distribution JSON.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GrantProof {
+    pub beneficiary: String,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_period: i64,
+    pub duration: i64,
+    /// Hex-encoded sibling hashes, leaf level first.
+    pub proof: Vec<String>,
+}
+
+/// The JSON document produced for a distribution.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Distribution {
+    /// Hex-encoded Merkle root to pass to `initialize_distribution`.
+    pub root: String,
+    /// Sum of all grants; use it as the distribution's `total_amount`.
+    pub total_amount: u64,
+    pub grants: Vec<GrantProof>,
+}
+
+impl Distribution {
+    /// Builds the tree over `grants` and collects each grant's proof.
+    pub fn build(grants: &[Grant]) -> Result<Self, MerkleError> {
+        if grants.is_empty() {
+            return Err(MerkleError::Empty);
+        }
+        let total_amount = grants
+            .iter()
+            .try_fold(0u64, |total, grant| total.checked_add(grant.amount))
+            .ok_or(MerkleError::Overflow)?;
+        let tree = MerkleTree::new(grants.iter().map(Grant::leaf).collect());
+        let grants = grants
+            .iter()
+            .enumerate()
+            .map(|(i, grant)| GrantProof {
+                beneficiary: grant.beneficiary.to_string(),
+                amount: grant.amount,
+                start_time: grant.start_time,
+                cliff_period: grant.cliff_period,
+                duration: grant.duration,
+                proof: tree.proof(i).iter().map(hex::encode).collect(),
+            })
+            .collect();
+        Ok(Self {
+            root: hex::encode(tree.root()),
+            total_amount,
+            grants,
+        })
+    }
+
+    /// Returns the entry for `beneficiary`, if the distribution contains one.
+    pub fn find(&self, beneficiary: &Pubkey) -> Option<&GrantProof> {
+        let beneficiary = beneficiary.to_string();
+        self.grants.iter().find(|grant| grant.beneficiary == beneficiary)
+    }
+
+    /// Verifies every proof against the root, returning the beneficiaries whose proofs fail.
+    pub fn verify(&self) -> Result<Vec<String>, MerkleError> {
+        let root = decode_hash(&self.root)?;
+        let mut failures = Vec::new();
+        for grant in &self.grants {
+            if !grant.verify(root)? {
+                failures.push(grant.beneficiary.clone());
+            }
+        }
+        Ok(failures)
+    }
+}
+
+impl GrantProof {
+    /// Decodes the proof into the byte arrays expected by `claim_merkle`.
+    pub fn proof_bytes(&self) -> Result<Vec<[u8; 32]>, MerkleError> {
+        self.proof.iter().map(|node| decode_hash(node)).collect()
+    }
+
+    /// Verifies this grant's proof against `root`.
+    pub fn verify(&self, root: [u8; 32]) -> Result<bool, MerkleError> {
+        let beneficiary = Pubkey::from_str(&self.beneficiary).map_err(|_| {
+            MerkleError::InvalidBeneficiary {
+                row: 0,
+                value: self.beneficiary.clone(),
+            }
+        })?;
+        let leaf = merkle_leaf(
+            &beneficiary,
+            self.amount,
+            self.start_time,
+            self.cliff_period,
+            self.duration,
+        );
+        Ok(verify_merkle_proof(&self.proof_bytes()?, root, leaf))
+    }
+}
+
+/// Decodes a hex-encoded 32-byte hash.
+pub fn decode_hash(value: &str) -> Result<[u8; 32], MerkleError> {
+    let mut hash = [0u8; 32];
+    hex::decode_to_slice(value, &mut hash).map_err(|_| MerkleError::InvalidHex(value.to_string()))?;
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv_for(beneficiaries: &[Pubkey]) -> String {
+        let mut csv = String::from("beneficiary,amount,start_time,cliff_period,duration\n");
+        for (i, beneficiary) in beneficiaries.iter().enumerate() {
+            csv.push_str(&format!("{},{},1700000000,0,600\n", beneficiary, (i as u64 + 1) * 1_000));
+        }
+        csv
+    }
+
+    #[test]
+    fn builds_verifiable_distribution() {
+        let beneficiaries: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let grants = read_grants(csv_for(&beneficiaries).as_bytes()).unwrap();
+        let distribution = Distribution::build(&grants).unwrap();
+
+        assert_eq!(distribution.total_amount, 15_000);
+        assert!(distribution.verify().unwrap().is_empty());
+        assert_eq!(distribution.find(&beneficiaries[2]).unwrap().amount, 3_000);
+    }
+
+    #[test]
+    fn tampered_amount_fails_verification() {
+        let beneficiaries: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let grants = read_grants(csv_for(&beneficiaries).as_bytes()).unwrap();
+        let mut distribution = Distribution::build(&grants).unwrap();
+        distribution.grants[1].amount += 1;
+
+        assert_eq!(distribution.verify().unwrap(), vec![beneficiaries[1].to_string()]);
+    }
+
+    #[test]
+    fn rejects_duplicate_beneficiaries() {
+        let beneficiary = Pubkey::new_unique();
+        let err = read_grants(csv_for(&[beneficiary, beneficiary]).as_bytes()).unwrap_err();
+        assert!(matches!(err, MerkleError::DuplicateBeneficiary { row: 2, .. }));
+    }
+}
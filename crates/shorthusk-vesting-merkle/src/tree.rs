@@ -0,0 +1,89 @@
+use shorthusk_vesting::merkle_node;
+
+/// A Merkle tree built with the same node hashing as the on-chain program.
+///
+/// Layers are stored bottom-up. A node without a sibling is promoted unchanged to the next layer,
+/// so its proof simply has no entry for that level.
+pub struct MerkleTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Builds a tree over `leaves`, which must not be empty.
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(!leaves.is_empty(), "a Merkle tree needs at least one leaf");
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => merkle_node(*left, *right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers[0].is_empty()
+    }
+
+    /// Returns the sibling hashes needed to prove the leaf at `index`.
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shorthusk_vesting::verify_merkle_proof;
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn single_leaf_is_its_own_root() {
+        let tree = MerkleTree::new(leaves(1));
+        assert_eq!(tree.root(), [0; 32]);
+        assert!(tree.proof(0).is_empty());
+    }
+
+    #[test]
+    fn every_proof_verifies_for_odd_and_even_sizes() {
+        for n in 1..=9 {
+            let tree = MerkleTree::new(leaves(n));
+            for (i, leaf) in leaves(n).into_iter().enumerate() {
+                assert!(verify_merkle_proof(&tree.proof(i), tree.root(), leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_does_not_verify_another_leaf() {
+        let tree = MerkleTree::new(leaves(4));
+        assert!(!verify_merkle_proof(&tree.proof(0), tree.root(), [1; 32]));
+    }
+}
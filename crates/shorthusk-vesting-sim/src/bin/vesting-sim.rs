@@ -0,0 +1,63 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use clap::Parser;
+use shorthusk_vesting_client::rpc::vesting_accounts;
+use shorthusk_vesting_sim::{calendar, schedule_range, timeline, write_csv, Interval};
+use solana_rpc_client::rpc_client::RpcClient;
+
+/// Simulates when the grants of a vault unlock and exports the timeline as CSV and iCalendar.
+#[derive(Parser)]
+#[command(name = "vesting-sim", version)]
+struct Cli {
+    /// RPC endpoint.
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Mint of the vault whose grants are simulated.
+    #[arg(long)]
+    mint: Pubkey,
+    /// Aggregation period: `day` or `month`.
+    #[arg(long, default_value = "month")]
+    interval: Interval,
+    /// First unix timestamp to report; defaults to the earliest grant start.
+    #[arg(long)]
+    from: Option<i64>,
+    /// Last unix timestamp to report; defaults to the latest grant end.
+    #[arg(long)]
+    to: Option<i64>,
+    /// Writes the timeline to this CSV file instead of stdout.
+    #[arg(long)]
+    csv: Option<PathBuf>,
+    /// Writes cliff and fully-vested dates of every grant to this .ics file.
+    #[arg(long)]
+    ics: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.url);
+    let grants = vesting_accounts(&rpc, &cli.mint)?;
+    let Some((start, end)) = schedule_range(&grants) else {
+        bail!("no vesting accounts found for mint {}", cli.mint);
+    };
+
+    let periods = timeline(
+        &grants,
+        cli.from.unwrap_or(start),
+        cli.to.unwrap_or(end),
+        cli.interval,
+    );
+    match cli.csv {
+        Some(path) => write_csv(File::create(path)?, &periods)?,
+        None => write_csv(std::io::stdout(), &periods)?,
+    }
+    if let Some(path) = cli.ics {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        fs::write(path, calendar(&grants, now))?;
+    }
+    eprintln!("Simulated {} grants over {} periods", grants.len(), periods.len());
+    Ok(())
+}
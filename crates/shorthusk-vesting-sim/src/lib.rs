@@ -0,0 +1,264 @@
+//! Offline unlock simulation for `shorthusk-vesting` grants.
+//!
+//! Uses the program's own `VestingAccount::vested_amount` and `VestingAccount::end_time`, so cliffs,
+//! terminations and reduced grants unlock and finish exactly as they would on-chain. [`timeline`] aggregates unlocks per day or month, and [`write_csv`] and
+//! [`calendar`] export the result for spreadsheets and calendar apps.
+
+use std::io::Write;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeDelta, Utc};
+use shorthusk_vesting::VestingAccount;
+
+/// Width of a timeline period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interval {
+    Day,
+    Month,
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "day" => Ok(Self::Day),
+            "month" => Ok(Self::Month),
+            _ => Err(format!("unknown interval `{value}`, expected `day` or `month`")),
+        }
+    }
+}
+
+impl Interval {
+    /// Start of the period containing `date`.
+    fn floor(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Month => date.with_day(1).unwrap(),
+        }
+    }
+
+    fn next(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date + TimeDelta::days(1),
+            Self::Month => date + Months::new(1),
+        }
+    }
+
+    fn label(self, date: NaiveDate) -> String {
+        match self {
+            Self::Day => date.format("%Y-%m-%d").to_string(),
+            Self::Month => date.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Tokens unlocking across all grants in one period.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Period {
+    /// Period label, `YYYY-MM-DD` or `YYYY-MM`.
+    pub label: String,
+    /// Unix timestamp of the period's first second (UTC).
+    pub start: i64,
+    /// Tokens vesting during the period.
+    pub unlocked: u64,
+    /// Tokens vested by the start of the next period.
+    pub cumulative: u64,
+}
+
+fn vested(grant: &VestingAccount, now: i64) -> u64 {
+    // The u128 intermediate cannot overflow for u64 amounts and i64 durations.
+    grant.vested_amount(now).expect("vesting math overflow")
+}
+
+fn end_time(grant: &VestingAccount) -> i64 {
+    grant.end_time().expect("vesting math overflow")
+}
+
+fn date_of(timestamp: i64) -> NaiveDate {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+fn timestamp_of(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp()
+}
+
+/// First start and last end time over `grants`, or `None` if there are none.
+pub fn schedule_range(grants: &[(Pubkey, VestingAccount)]) -> Option<(i64, i64)> {
+    let start = grants.iter().map(|(_, grant)| grant.start_time).min()?;
+    let end = grants.iter().map(|(_, grant)| end_time(grant)).max()?;
+    Some((start, end))
+}
+
+/// Aggregates unlocks of `grants` per `interval`, for every period overlapping `from..=to`.
+pub fn timeline(grants: &[(Pubkey, VestingAccount)], from: i64, to: i64, interval: Interval) -> Vec<Period> {
+    let total_vested = |now: i64| -> u64 { grants.iter().map(|(_, grant)| vested(grant, now)).sum() };
+
+    let mut periods = Vec::new();
+    let mut date = interval.floor(date_of(from));
+    let mut before = total_vested(timestamp_of(date));
+    while timestamp_of(date) <= to {
+        let next = interval.next(date);
+        let cumulative = total_vested(timestamp_of(next));
+        periods.push(Period {
+            label: interval.label(date),
+            start: timestamp_of(date),
+            unlocked: cumulative - before,
+            cumulative,
+        });
+        before = cumulative;
+        date = next;
+    }
+    periods
+}
+
+/// Writes `periods` as CSV with the header `period,unlocked,cumulative`.
+pub fn write_csv<W: Write>(writer: W, periods: &[Period]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["period", "unlocked", "cumulative"])?;
+    for period in periods {
+        writer.write_record([
+            period.label.clone(),
+            period.unlocked.to_string(),
+            period.cumulative.to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn ics_time(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Builds an iCalendar file with a cliff and a fully-vested event per grant. Revoked and fully claimed grants
+/// are left out since nothing more unlocks for them. `generated_at` is used as each event's `DTSTAMP`.
+pub fn calendar(grants: &[(Pubkey, VestingAccount)], generated_at: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//shorthusk//vesting-sim//EN".to_string(),
+    ];
+    let mut event = |uid: String, at: i64, summary: String| {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{uid}@shorthusk-vesting"),
+            format!("DTSTAMP:{}", ics_time(generated_at)),
+            format!("DTSTART:{}", ics_time(at)),
+            format!("SUMMARY:{summary}"),
+            "END:VEVENT".to_string(),
+        ]);
+    };
+    for (address, grant) in grants.iter().filter(|(_, grant)| grant.is_active()) {
+        let cliff = grant.start_time.saturating_add(grant.cliff_period);
+        let end = end_time(grant);
+        if grant.cliff_period > 0 {
+            event(
+                format!("{address}-cliff"),
+                cliff,
+                format!("Cliff: {} unlock for {}", vested(grant, cliff + 1), grant.beneficiary),
+            );
+        }
+        event(
+            format!("{address}-end"),
+            end,
+            format!("Fully vested: {} for {}", grant.total_amount, grant.beneficiary),
+        );
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    // 2024-01-01T00:00:00Z
+    const JAN_1: i64 = 1_704_067_200;
+
+    fn grant(start_time: i64, cliff_period: i64, duration: i64, total_amount: u64) -> (Pubkey, VestingAccount) {
+        (
+            Pubkey::new_unique(),
+            VestingAccount::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                start_time,
+                cliff_period,
+                duration,
+                total_amount,
+                255,
+                false,
+            ),
+        )
+    }
+
+    #[test]
+    fn daily_timeline_accounts_for_every_token() {
+        let grants = [grant(JAN_1, 2 * DAY, 10 * DAY, 1_000), grant(JAN_1 + DAY, 0, 4 * DAY, 400)];
+        let (from, to) = schedule_range(&grants).unwrap();
+        let periods = timeline(&grants, from, to, Interval::Day);
+
+        assert_eq!(periods.first().unwrap().label, "2024-01-01");
+        assert_eq!(periods.last().unwrap().label, "2024-01-11");
+        // Nothing unlocks on the first day; the second grant has no cliff and starts on day two.
+        assert_eq!(periods[0].unlocked, 0);
+        assert_eq!(periods[1].unlocked, 100);
+        // Day three crosses the first grant's cliff, releasing its first three days at once.
+        assert_eq!(periods[2].unlocked, 300 + 100);
+        assert_eq!(periods.iter().map(|period| period.unlocked).sum::<u64>(), 1_400);
+        assert_eq!(periods.last().unwrap().cumulative, 1_400);
+    }
+
+    #[test]
+    fn monthly_timeline_respects_calendar_months() {
+        let grants = [grant(JAN_1, 0, 60 * DAY, 600)];
+        let periods = timeline(&grants, JAN_1, JAN_1 + 60 * DAY, Interval::Month);
+
+        let labels: Vec<&str> = periods.iter().map(|period| period.label.as_str()).collect();
+        assert_eq!(labels, ["2024-01", "2024-02", "2024-03"]);
+        assert_eq!(periods[0].unlocked, 310);
+        assert_eq!(periods[1].unlocked, 290);
+        assert_eq!(periods[2].unlocked, 0);
+    }
+
+    #[test]
+    fn calendar_has_cliff_and_end_events() {
+        let mut revoked = grant(JAN_1, DAY, 10 * DAY, 1_000);
+        revoked.1.revoked = true;
+        let grants = [grant(JAN_1, DAY, 10 * DAY, 1_000), grant(JAN_1, 0, DAY, 5), revoked];
+        let ics = calendar(&grants, JAN_1);
+
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("DTSTART:20240102T000000Z\r\nSUMMARY:Cliff: 100 unlock for"));
+        assert!(ics.contains("DTSTART:20240111T000000Z\r\nSUMMARY:Fully vested: 1000 for"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn reduced_and_terminated_grants_finish_early() {
+        // Reduced from 1,000 to 600 at the same rate, so it is fully vested after six of its ten days.
+        let mut reduced = grant(JAN_1, 0, 10 * DAY, 1_000);
+        reduced.1.total_amount = 600;
+        // Terminated after four days, keeping what had vested by then.
+        let mut terminated = grant(JAN_1, 0, 10 * DAY, 1_000);
+        terminated.1.terminated_at = JAN_1 + 4 * DAY;
+        terminated.1.total_amount = 400;
+        let mut claimed = grant(JAN_1, 0, 8 * DAY, 100);
+        claimed.1.claimed_amount = 100;
+        let grants = [reduced, terminated, claimed];
+
+        assert_eq!(schedule_range(&grants), Some((JAN_1, JAN_1 + 8 * DAY)));
+        assert_eq!(schedule_range(&grants[..2]), Some((JAN_1, JAN_1 + 6 * DAY)));
+        let ics = calendar(&grants, JAN_1);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART:20240107T000000Z\r\nSUMMARY:Fully vested: 600 for"));
+        assert!(ics.contains("DTSTART:20240105T000000Z\r\nSUMMARY:Fully vested: 400 for"));
+    }
+}
@@ -0,0 +1,59 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use clap::Parser;
+use shorthusk_vesting_indexer::store::Store;
+use shorthusk_vesting_statements::statements;
+use solana_rpc_client::rpc_client::RpcClient;
+
+/// Writes one CSV statement per beneficiary listing their claims, instant unlocks and good-leaver payouts of a
+/// mint, from a database filled by `vesting-indexer`.
+#[derive(Parser)]
+#[command(name = "vesting-statements", version)]
+struct Cli {
+    #[arg(long, default_value = "vesting-events.sqlite")]
+    db: PathBuf,
+    #[arg(long)]
+    mint: Pubkey,
+    /// Only include payouts in this calendar year (UTC).
+    #[arg(long)]
+    year: Option<i32>,
+    /// Mint decimals for UI amounts; fetched from the RPC endpoint if omitted.
+    #[arg(long)]
+    decimals: Option<u8>,
+    /// RPC endpoint, only used to look up the mint's decimals.
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Directory for the statements, named `<beneficiary>.csv` or `<beneficiary>-<year>.csv`.
+    #[arg(long, default_value = "statements")]
+    out_dir: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let decimals = match cli.decimals {
+        Some(decimals) => decimals,
+        None => RpcClient::new(cli.url).get_token_supply(&cli.mint)?.decimals,
+    };
+    let store = Store::open(&cli.db).with_context(|| format!("opening {}", cli.db.display()))?;
+    let statements = statements(&store.events(None, Some(&cli.mint))?, cli.year)?;
+
+    fs::create_dir_all(&cli.out_dir)?;
+    for statement in &statements {
+        let name = match cli.year {
+            Some(year) => format!("{}-{year}.csv", statement.beneficiary),
+            None => format!("{}.csv", statement.beneficiary),
+        };
+        let path = cli.out_dir.join(name);
+        let file = File::create(&path).with_context(|| format!("creating {}", path.display()))?;
+        statement.write_csv(file, decimals)?;
+    }
+    eprintln!(
+        "Wrote {} statements to {}",
+        statements.len(),
+        cli.out_dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,341 @@
+//! Per-beneficiary payout statements built from indexed events.
+//!
+//! Every `ClaimEvent` and `InstantUnlockEvent`, and every `RevokeVestingEvent` with a non-zero
+//! `beneficiary_amount` (a good-leaver revoke), is a transfer from the vault to the grant's beneficiary.
+//! Claims and instant unlocks logged in the earlier event layout count too; earlier `RevokeVestingEvent`s
+//! do not name the beneficiary and are left out.
+//! [`statements`] groups them by beneficiary in chain order, dated by the block time of the transaction, and
+//! [`Statement::write_csv`] renders one statement with amounts in UI units and a total row after each
+//! calendar year (UTC).
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anchor_lang::prelude::Pubkey;
+use chrono::{DateTime, Datelike, Utc};
+use shorthusk_vesting_indexer::legacy::LegacyEvent;
+use shorthusk_vesting_indexer::store::IndexedEvent;
+use shorthusk_vesting_indexer::VestingEvent;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StatementError {
+    #[error("transaction {signature} has no block time; re-index it from a node that reports one")]
+    MissingBlockTime { signature: String },
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+}
+
+/// How tokens reached the beneficiary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoutKind {
+    Claim,
+    InstantUnlock,
+    /// The vested amount paid out by `revoke_vesting_good_leaver`.
+    GoodLeaver,
+}
+
+impl PayoutKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Claim => "claim",
+            Self::InstantUnlock => "instant_unlock",
+            Self::GoodLeaver => "good_leaver",
+        }
+    }
+}
+
+/// A single transfer to the beneficiary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Payout {
+    /// Unix timestamp of the block.
+    pub timestamp: i64,
+    pub signature: String,
+    pub vesting_account: Pubkey,
+    pub kind: PayoutKind,
+    pub amount: u64,
+}
+
+impl Payout {
+    fn year(&self) -> i32 {
+        date_time(self.timestamp).year()
+    }
+}
+
+/// Every payout of one beneficiary, in chain order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement {
+    pub beneficiary: Pubkey,
+    pub payouts: Vec<Payout>,
+}
+
+fn date_time(timestamp: i64) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_default()
+}
+
+/// Formats base units as a decimal string with exactly `decimals` fractional digits.
+pub fn ui_amount(amount: u128, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let scale = 10u128.pow(decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / scale,
+        amount % scale,
+        width = decimals as usize
+    )
+}
+
+impl Statement {
+    /// Sum of payouts per calendar year, in year order.
+    pub fn year_totals(&self) -> Vec<(i32, u128)> {
+        let mut totals = BTreeMap::new();
+        for payout in &self.payouts {
+            *totals.entry(payout.year()).or_insert(0u128) += payout.amount as u128;
+        }
+        totals.into_iter().collect()
+    }
+
+    /// Writes the statement as CSV with the header `date,kind,signature,vesting_account,amount`. Dates are
+    /// RFC 3339 in UTC. Each year's payouts are followed by a row with the year as date, kind `total` and
+    /// the year's sum.
+    pub fn write_csv<W: Write>(&self, writer: W, decimals: u8) -> Result<(), StatementError> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["date", "kind", "signature", "vesting_account", "amount"])?;
+        let totals = self.year_totals();
+        for (year, total) in totals {
+            for payout in self.payouts.iter().filter(|payout| payout.year() == year) {
+                writer.write_record([
+                    date_time(payout.timestamp).format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                    payout.kind.label().to_string(),
+                    payout.signature.clone(),
+                    payout.vesting_account.to_string(),
+                    ui_amount(payout.amount as u128, decimals),
+                ])?;
+            }
+            writer.write_record([
+                year.to_string(),
+                "total".to_string(),
+                String::new(),
+                String::new(),
+                ui_amount(total, decimals),
+            ])?;
+        }
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+/// Groups the payouts among `events` by beneficiary, keeping only those in `year` if given. Other event
+/// types are ignored. Statements are ordered by beneficiary.
+pub fn statements(events: &[IndexedEvent], year: Option<i32>) -> Result<Vec<Statement>, StatementError> {
+    let mut by_beneficiary: BTreeMap<Pubkey, Vec<Payout>> = BTreeMap::new();
+    for indexed in events {
+        let (beneficiary, vesting_account, kind, amount) = match &indexed.event {
+            VestingEvent::Claim(event) => (event.beneficiary, event.vesting_account, PayoutKind::Claim, event.amount),
+            VestingEvent::InstantUnlock(event) => (
+                event.beneficiary,
+                event.vesting_account,
+                PayoutKind::InstantUnlock,
+                event.amount,
+            ),
+            VestingEvent::Legacy(LegacyEvent::Claim(event)) => {
+                (event.beneficiary, event.vesting_account, PayoutKind::Claim, event.amount)
+            }
+            VestingEvent::Legacy(LegacyEvent::InstantUnlock(event)) => (
+                event.beneficiary,
+                event.vesting_account,
+                PayoutKind::InstantUnlock,
+                event.amount,
+            ),
+            VestingEvent::RevokeVesting(event) if event.beneficiary_amount > 0 => (
+                event.beneficiary,
+                event.vesting_account,
+                PayoutKind::GoodLeaver,
+                event.beneficiary_amount,
+            ),
+            _ => continue,
+        };
+        let timestamp = indexed.block_time.ok_or_else(|| StatementError::MissingBlockTime {
+            signature: indexed.signature.clone(),
+        })?;
+        let payout = Payout {
+            timestamp,
+            signature: indexed.signature.clone(),
+            vesting_account,
+            kind,
+            amount,
+        };
+        if year.is_some_and(|year| payout.year() != year) {
+            continue;
+        }
+        by_beneficiary.entry(beneficiary).or_default().push(payout);
+    }
+    Ok(by_beneficiary
+        .into_iter()
+        .map(|(beneficiary, payouts)| Statement { beneficiary, payouts })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shorthusk_vesting::{ClaimEvent, InstantUnlockEvent, PauseEvent, RevokeVestingEvent};
+    use shorthusk_vesting_indexer::legacy::ClaimEventV1;
+
+    // 2024-12-31T23:00:00Z
+    const NYE_2024: i64 = 1_735_686_000;
+    const HOUR: i64 = 3_600;
+
+    fn indexed(signature: &str, block_time: Option<i64>, event: VestingEvent) -> IndexedEvent {
+        IndexedEvent {
+            signature: signature.to_string(),
+            slot: 1,
+            block_time,
+            log_index: 0,
+            event,
+        }
+    }
+
+    fn claim(beneficiary: Pubkey, amount: u64) -> VestingEvent {
+        VestingEvent::Claim(ClaimEvent {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary,
+            amount,
+            mint: Pubkey::default(),
+            claimed_amount: amount,
+            total_amount: amount,
+            vault_balance: 0,
+            timestamp: 0,
+            slot: 1,
+        })
+    }
+
+    #[test]
+    fn formats_ui_amounts_exactly() {
+        assert_eq!(ui_amount(1_500_000_000, 9), "1.500000000");
+        assert_eq!(ui_amount(42, 6), "0.000042");
+        assert_eq!(ui_amount(42, 0), "42");
+        assert_eq!(ui_amount(u64::MAX as u128 * 3, 9), "55340232221.128654845");
+    }
+
+    #[test]
+    fn groups_payouts_by_beneficiary_and_year() {
+        let [alice, bob] = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let events = [
+            indexed("a", Some(NYE_2024), claim(alice, 1_000_000)),
+            indexed("b", Some(NYE_2024), claim(bob, 5)),
+            indexed(
+                "c",
+                Some(NYE_2024 + 2 * HOUR),
+                VestingEvent::Pause(PauseEvent {
+                    vesting_account: Pubkey::new_unique(),
+                    admin: Pubkey::new_unique(),
+                    mint: Pubkey::default(),
+                    claimed_amount: 0,
+                    total_amount: 0,
+                    timestamp: 0,
+                    slot: 1,
+                }),
+            ),
+            indexed("d", Some(NYE_2024 + 2 * HOUR), claim(alice, 2_500_000)),
+            indexed(
+                "e",
+                Some(NYE_2024 + 3 * HOUR),
+                VestingEvent::InstantUnlock(InstantUnlockEvent {
+                    vesting_account: Pubkey::new_unique(),
+                    beneficiary: alice,
+                    admin: Pubkey::new_unique(),
+                    amount: 500_000,
+                    mint: Pubkey::default(),
+                    claimed_amount: 500_000,
+                    total_amount: 500_000,
+                    vault_balance: 0,
+                    timestamp: 0,
+                    slot: 1,
+                }),
+            ),
+        ];
+        let all = statements(&events, None).unwrap();
+        let alice_statement = all.iter().find(|statement| statement.beneficiary == alice).unwrap();
+
+        assert_eq!(all.len(), 2);
+        assert_eq!(alice_statement.year_totals(), [(2024, 1_000_000), (2025, 3_000_000)]);
+        let in_2025 = statements(&events, Some(2025)).unwrap();
+        assert_eq!(in_2025.len(), 1);
+        assert_eq!(in_2025[0].payouts[1].kind, PayoutKind::InstantUnlock);
+
+        let mut csv = Vec::new();
+        alice_statement.write_csv(&mut csv, 6).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&csv).unwrap().lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "date,kind,signature,vesting_account,amount");
+        assert!(lines[1].starts_with("2024-12-31T23:00:00Z,claim,a,"));
+        assert!(lines[1].ends_with(",1.000000"));
+        assert_eq!(lines[2], "2024,total,,,1.000000");
+        assert!(lines[4].starts_with("2025-01-01T02:00:00Z,instant_unlock,e,"));
+        assert_eq!(lines[5], "2025,total,,,3.000000");
+    }
+
+    #[test]
+    fn includes_good_leaver_payouts() {
+        let beneficiary = Pubkey::new_unique();
+        let revoke = |beneficiary_amount| {
+            VestingEvent::RevokeVesting(RevokeVestingEvent {
+                vesting_account: Pubkey::new_unique(),
+                beneficiary,
+                admin: Pubkey::new_unique(),
+                remaining_amount: 10,
+                beneficiary_amount,
+                mint: Pubkey::default(),
+                claimed_amount: beneficiary_amount,
+                total_amount: beneficiary_amount,
+                vault_balance: 0,
+                timestamp: 0,
+                slot: 1,
+            })
+        };
+        let events = [
+            indexed("a", Some(NYE_2024), revoke(0)),
+            indexed("b", Some(NYE_2024), revoke(750_000)),
+        ];
+        let all = statements(&events, None).unwrap();
+
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].payouts.len(), 1);
+        assert_eq!(all[0].payouts[0].kind, PayoutKind::GoodLeaver);
+        assert_eq!(all[0].payouts[0].amount, 750_000);
+        let mut csv = Vec::new();
+        all[0].write_csv(&mut csv, 6).unwrap();
+        assert!(std::str::from_utf8(&csv).unwrap().contains(",good_leaver,b,"));
+    }
+
+    #[test]
+    fn includes_claims_in_the_earlier_layout() {
+        let beneficiary = Pubkey::new_unique();
+        let legacy = VestingEvent::Legacy(LegacyEvent::Claim(ClaimEventV1 {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary,
+            amount: 40,
+            mint: Pubkey::default(),
+        }));
+        let events = [
+            indexed("a", Some(NYE_2024), legacy),
+            indexed("b", Some(NYE_2024), claim(beneficiary, 2)),
+        ];
+        let all = statements(&events, None).unwrap();
+
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].year_totals(), [(2024, 42)]);
+    }
+
+    #[test]
+    fn requires_block_times() {
+        let events = [indexed("a", None, claim(Pubkey::new_unique(), 1))];
+        assert!(matches!(
+            statements(&events, None),
+            Err(StatementError::MissingBlockTime { signature }) if signature == "a"
+        ));
+    }
+}
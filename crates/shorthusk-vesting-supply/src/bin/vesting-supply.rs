@@ -0,0 +1,76 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use shorthusk_vesting_client::pda;
+use shorthusk_vesting_client::rpc::vesting_accounts;
+use shorthusk_vesting_supply::{GrantTotals, SupplyReport};
+use solana_rpc_client::rpc_client::RpcClient;
+use tiny_http::{Header, Response, Server};
+
+/// Reports locked, vested-unclaimed, claimed and circulating supply of a vested mint as JSON.
+#[derive(Parser)]
+#[command(name = "vesting-supply", version)]
+struct Cli {
+    /// RPC endpoint.
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    #[arg(long)]
+    mint: Pubkey,
+    /// Unix timestamp to report at; defaults to the current time.
+    #[arg(long)]
+    at: Option<i64>,
+    /// Serves the report over HTTP on this address (e.g. `0.0.0.0:8080`) instead of printing it once.
+    /// Each request fetches fresh data.
+    #[arg(long)]
+    serve: Option<String>,
+}
+
+fn report(rpc: &RpcClient, mint: &Pubkey, at: Option<i64>) -> Result<SupplyReport> {
+    let at = match at {
+        Some(at) => at,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+    };
+    let supply = rpc.get_token_supply(mint)?;
+    let vault_balance = rpc.get_token_account_balance(&pda::vault_token_account(mint))?;
+    let grants = vesting_accounts(rpc, mint)?;
+    Ok(SupplyReport::new(
+        mint,
+        at,
+        supply.decimals,
+        supply.amount.parse()?,
+        vault_balance.amount.parse()?,
+        GrantTotals::at(&grants, at),
+    ))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.url);
+
+    let Some(address) = cli.serve else {
+        println!("{}", serde_json::to_string_pretty(&report(&rpc, &cli.mint, cli.at)?)?);
+        return Ok(());
+    };
+
+    let server = Server::http(&address).map_err(|err| anyhow!("binding {address}: {err}"))?;
+    let json = Header::from_bytes("Content-Type", "application/json").unwrap();
+    eprintln!("Serving supply report for {} on http://{address}", cli.mint);
+    for request in server.incoming_requests() {
+        let response = match report(&rpc, &cli.mint, cli.at) {
+            Ok(report) => Response::from_string(serde_json::to_string(&report)?).with_header(json.clone()),
+            Err(err) => {
+                eprintln!("{err:#}");
+                Response::from_string(serde_json::json!({ "error": err.to_string() }).to_string())
+                    .with_header(json.clone())
+                    .with_status_code(502)
+            }
+        };
+        // A client that disconnected early must not stop the server.
+        if let Err(err) = request.respond(response) {
+            eprintln!("responding: {err}");
+        }
+    }
+    Ok(())
+}
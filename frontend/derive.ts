@@ -38,3 +38,35 @@ export const getVestingPda = (vault: PublicKey, mint: PublicKey, beneficiary: Pu
     PROGRAM_ID // your program ID
   );
 };
+
+/**
+ * Derives the PDA for a Merkle distribution of a vault.
+ */
+export const getDistributionPda = (vault: PublicKey, id: number | bigint): [PublicKey, number] => {
+  const idBuffer = Buffer.alloc(8);
+  idBuffer.writeBigUInt64LE(BigInt(id));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("distribution"), vault.toBuffer(), idBuffer],
+    PROGRAM_ID
+  );
+};
+
+/**
+ * Derives the PDA for a vesting account created from a Merkle distribution.
+ */
+export const getMerkleVestingPda = (distribution: PublicKey, beneficiary: PublicKey): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("vesting"), distribution.toBuffer(), beneficiary.toBuffer()],
+    PROGRAM_ID
+  );
+};
+
+/**
+ * Derives the PDA for the receipt that marks a beneficiary's Merkle leaf as claimed.
+ */
+export const getMerkleClaimReceiptPda = (distribution: PublicKey, beneficiary: PublicKey): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("merkle_receipt"), distribution.toBuffer(), beneficiary.toBuffer()],
+    PROGRAM_ID
+  );
+};
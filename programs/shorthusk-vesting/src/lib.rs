@@ -1,5 +1,6 @@
-use anchor_lang::{prelude::*, AccountDeserialize, AccountSerialize};
+use anchor_lang::{prelude::*, solana_program::hash::hashv, AccountDeserialize, AccountSerialize};
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+#[cfg(not(feature = "no-entrypoint"))]
 use solana_security_txt::security_txt;
 
 declare_id!("VestF59gEqPp83UV8JKn85zXsEn1SuLq8mdz8QxxKzY");
@@ -17,6 +18,22 @@ security_txt! {
     auditors: "None"
 }
 
+/// Emits an event to the program log, or with `emit_cpi!` when built with the `event-cpi` feature, so
+/// indexers can read it from the transaction's inner instructions. `emit_cpi!` looks up the event authority
+/// through a variable named `ctx`, which is why the context is passed in.
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {{
+        let event = $event;
+        #[cfg(feature = "event-cpi")]
+        {
+            let ctx = &$ctx;
+            emit_cpi!(event);
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        emit!(event);
+    }};
+}
+
 #[program]
 pub mod shorthusk_vesting {
     use super::*;
@@ -28,21 +45,31 @@ pub mod shorthusk_vesting {
         vault.admin = ctx.accounts.payer.key();
         vault.paused = false;
         vault.initialized = true;
-        emit!(VaultInitializedEvent {
+        vault.irrevocable_outstanding = 0;
+        vault.total_committed = 0;
+        vault.total_claimed = 0;
+        vault.active_grants = 0;
+        vault.distribution_reserved = 0;
+        let clock = Clock::get()?;
+        emit_event!(ctx, VaultInitializedEvent {
             vault: vault.key(),
             admin: vault.admin,
             mint: ctx.accounts.mint.key(),
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
     /// Initializes a vesting account with a linear token release schedule.
+    /// Irrevocable grants can never be revoked, terminated, paused or redirected by the admin.
     pub fn initialize_vesting(
         ctx: Context<InitializeVesting>,
         start_time: i64,
         cliff_period: i64,
         duration: i64,
         total_amount: u64,
+        irrevocable: bool,
     ) -> Result<()> {
         require!(
             ctx.accounts.vault.initialized,
@@ -53,33 +80,39 @@ pub mod shorthusk_vesting {
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
-        require!(duration > 0, VestingError::InvalidDuration);
-        require!(
-            cliff_period >= 0 && cliff_period <= duration,
-            VestingError::InvalidCliffPeriod
-        );
-        require!(total_amount > 0, VestingError::InvalidAmount);
+        validate_schedule(cliff_period, duration, total_amount)?;
         let vesting_account = &mut ctx.accounts.vesting_account;
         require!(
             !vesting_account.initialized,
             VestingError::AlreadyInitialized
         );
-        vesting_account.beneficiary = ctx.accounts.beneficiary.key();
-        vesting_account.mint = ctx.accounts.mint.key(); // Store mint
-        vesting_account.start_time = start_time;
-        vesting_account.cliff_period = cliff_period;
-        vesting_account.duration = duration;
-        vesting_account.total_amount = total_amount;
-        vesting_account.claimed_amount = 0;
-        vesting_account.paused = false;
-        vesting_account.initialized = true;
-        vesting_account.revoked = false;
-        vesting_account.bump = ctx.bumps.vesting_account;
-        emit!(VestingInitializedEvent {
+        vesting_account.set_inner(VestingAccount::new(
+            ctx.accounts.beneficiary.key(),
+            ctx.accounts.mint.key(),
+            start_time,
+            cliff_period,
+            duration,
+            total_amount,
+            ctx.bumps.vesting_account,
+            irrevocable,
+        ));
+        let vault = &mut ctx.accounts.vault;
+        vault.record_grant(total_amount)?;
+        if irrevocable {
+            vault.irrevocable_outstanding = vault
+                .irrevocable_outstanding
+                .checked_add(total_amount)
+                .ok_or(VestingError::InvalidAmount)?;
+        }
+        let clock = Clock::get()?;
+        emit_event!(ctx, VestingInitializedEvent {
             vesting_account: vesting_account.key(),
             beneficiary: vesting_account.beneficiary,
             mint: ctx.accounts.mint.key(),
             total_amount,
+            irrevocable,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
@@ -92,7 +125,10 @@ pub mod shorthusk_vesting {
         require!(vesting_account.initialized, VestingError::NotInitialized);
         require!(!vesting_account.revoked, VestingError::VestingRevoked);
         require!(!vesting_account.paused, VestingError::Paused);
-        require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        require!(
+            !vesting_account.blocked_by_vault_pause(ctx.accounts.vault.paused),
+            VestingError::VaultPaused
+        );
 
         let elapsed = clock.unix_timestamp - vesting_account.start_time;
         require!(elapsed >= 0, VestingError::InvalidTimestamp);
@@ -101,20 +137,9 @@ pub mod shorthusk_vesting {
             VestingError::CliffNotReached
         );
 
-        let vested_amount = if elapsed >= vesting_account.duration {
-            vesting_account.total_amount
-        } else {
-            vesting_account
-                .total_amount
-                .checked_mul(elapsed as u64)
-                .unwrap()
-                .checked_div(vesting_account.duration as u64)
-                .unwrap()
-        };
+        let vested_amount = vesting_account.vested_amount(clock.unix_timestamp)?;
 
-        let claimable = vested_amount
-            .checked_sub(vesting_account.claimed_amount)
-            .unwrap();
+        let claimable = vested_amount.saturating_sub(vesting_account.claimed_amount);
 
         require!(claimable > 0, VestingError::NothingToClaim);
 
@@ -126,6 +151,12 @@ pub mod shorthusk_vesting {
         );
 
         vesting_account.claimed_amount = new_claimed;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_payout(claimable)?;
+        vault.record_activity(true, vesting_account.is_active());
+        if vesting_account.irrevocable {
+            vault.irrevocable_outstanding = vault.irrevocable_outstanding.saturating_sub(claimable);
+        }
 
         let mint_key = ctx.accounts.mint.key();
         let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
@@ -141,16 +172,135 @@ pub mod shorthusk_vesting {
             signer,
         );
         token::transfer(cpi_ctx, claimable)?;
-        emit!(ClaimEvent {
+        ctx.accounts.vault_token_account.reload()?;
+        emit_event!(ctx, ClaimEvent {
             vesting_account: vesting_account.key(),
             beneficiary: ctx.accounts.beneficiary.key(),
             amount: claimable,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
 
         Ok(())
     }
     
+    /// Claims vested tokens from several grants of the signing beneficiary in one transaction. Remaining accounts
+    /// hold a (vesting account, vault, vault token account, destination token account) tuple per grant, so the
+    /// grants may come from different vaults and mints. Grants with nothing to claim right now (before the cliff,
+    /// fully claimed, paused, revoked or in a paused vault) are skipped; fails only if no grant had anything to claim.
+    pub fn claim_many<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            VestingError::NotEnoughAccounts
+        );
+        let grants = ctx.remaining_accounts.chunks_exact(4);
+        require!(
+            grants.remainder().is_empty(),
+            VestingError::InvalidAccountCount
+        );
+        let clock = Clock::get()?;
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let mut total_claimed: u64 = 0;
+
+        for accounts in grants {
+            let (vesting_info, vault_info, vault_token_info, destination_info) =
+                (&accounts[0], &accounts[1], &accounts[2], &accounts[3]);
+            require!(
+                vesting_info.is_writable
+                    && vault_info.is_writable
+                    && vault_token_info.is_writable
+                    && destination_info.is_writable,
+                VestingError::InvalidAccount
+            );
+
+            let mut vesting_account = Account::<VestingAccount>::try_from(vesting_info)?;
+            require_keys_eq!(
+                vesting_account.beneficiary,
+                beneficiary,
+                VestingError::Unauthorized
+            );
+            let mint_key = vesting_account.mint;
+
+            let mut vault = Account::<Vault>::try_from(vault_info)?;
+            let vault_seeds = &[b"vault", mint_key.as_ref(), &[vault.bump]];
+            let expected_vault = Pubkey::create_program_address(vault_seeds, ctx.program_id)
+                .map_err(|_| VestingError::InvalidAccount)?;
+            require_keys_eq!(vault_info.key(), expected_vault, VestingError::InvalidAccount);
+
+            let mut vault_token_account = Account::<TokenAccount>::try_from(vault_token_info)?;
+            require!(
+                vault_token_account.mint == mint_key && vault_token_account.owner == vault.key(),
+                VestingError::InvalidAccount
+            );
+            let destination = Account::<TokenAccount>::try_from(destination_info)?;
+            require_keys_eq!(destination.mint, mint_key, VestingError::InvalidAccount);
+
+            if !vesting_account.initialized
+                || vesting_account.revoked
+                || vesting_account.paused
+                || vesting_account.blocked_by_vault_pause(vault.paused)
+            {
+                continue;
+            }
+            let vested_amount = vesting_account.vested_amount(clock.unix_timestamp)?;
+            let claimable = vested_amount.saturating_sub(vesting_account.claimed_amount);
+            if claimable == 0 {
+                continue;
+            }
+
+            vesting_account.claimed_amount = vesting_account
+                .claimed_amount
+                .checked_add(claimable)
+                .ok_or(VestingError::InvalidAmount)?;
+            require!(
+                vesting_account.claimed_amount <= vesting_account.total_amount,
+                VestingError::InvalidAmount
+            );
+            vesting_account.exit(ctx.program_id)?;
+            vault.record_payout(claimable)?;
+            vault.record_activity(true, vesting_account.is_active());
+            if vesting_account.irrevocable {
+                vault.irrevocable_outstanding = vault.irrevocable_outstanding.saturating_sub(claimable);
+            }
+            vault.exit(ctx.program_id)?;
+
+            let signer = &[&vault_seeds[..]];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: vault_token_info.clone(),
+                    to: destination_info.clone(),
+                    authority: vault_info.clone(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, claimable)?;
+            vault_token_account.reload()?;
+            total_claimed = total_claimed
+                .checked_add(claimable)
+                .ok_or(VestingError::InvalidAmount)?;
+
+            emit_event!(ctx, ClaimEvent {
+                vesting_account: vesting_info.key(),
+                beneficiary,
+                amount: claimable,
+                mint: mint_key,
+                claimed_amount: vesting_account.claimed_amount,
+                total_amount: vesting_account.total_amount,
+                vault_balance: vault_token_account.amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+
+        require!(total_claimed > 0, VestingError::NothingToClaim);
+        Ok(())
+    }
+
     /// Allows the admin to claim tokens on behalf of a beneficiary.
     pub fn admin_claim(ctx: Context<AdminClaim>) -> Result<()> {
         let vesting_account = &mut ctx.accounts.vesting_account;
@@ -165,36 +315,24 @@ pub mod shorthusk_vesting {
         require!(!vesting_account.revoked, VestingError::VestingRevoked);
         require!(!vesting_account.paused, VestingError::Paused);
         require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        if vesting_account.irrevocable {
+            require_keys_eq!(
+                ctx.accounts.beneficiary_token_account.owner,
+                vesting_account.beneficiary,
+                VestingError::InvalidAccount
+            );
+        }
 
-        let current_time = clock.unix_timestamp as i64;
+        let current_time = clock.unix_timestamp;
         let elapsed = current_time - vesting_account.start_time;
         msg!("Current Time: {}, Start Time: {}, Elapsed: {}", current_time, vesting_account.start_time, elapsed);
-        let elapsed = if elapsed < 0 {
-            if elapsed < -60 {
-                return Err(VestingError::InvalidTimestamp.into());
-            }
-            0
-        } else {
-            elapsed
-        };
+        if elapsed < -60 {
+            return Err(VestingError::InvalidTimestamp.into());
+        }
 
-        let claimable = if elapsed <= vesting_account.cliff_period {
-            0
-        } else if elapsed >= vesting_account.duration {
-            vesting_account.total_amount
-                .checked_sub(vesting_account.claimed_amount)
-                .ok_or(VestingError::InvalidAmount)?
-        } else {
-            let vested_amount = vesting_account
-                .total_amount
-                .checked_mul(elapsed as u64)
-                .ok_or(VestingError::InvalidAmount)?
-                .checked_div(vesting_account.duration as u64)
-                .ok_or(VestingError::InvalidAmount)?;
-            vested_amount
-                .checked_sub(vesting_account.claimed_amount)
-                .ok_or(VestingError::InvalidAmount)?
-        };
+        let claimable = vesting_account
+            .vested_amount(current_time)?
+            .saturating_sub(vesting_account.claimed_amount);
 
         let new_claimed = vesting_account.claimed_amount.checked_add(claimable)
             .ok_or(VestingError::InvalidAmount)?;
@@ -208,6 +346,12 @@ pub mod shorthusk_vesting {
         }
 
         vesting_account.claimed_amount = new_claimed;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_payout(claimable)?;
+        vault.record_activity(true, vesting_account.is_active());
+        if vesting_account.irrevocable {
+            vault.irrevocable_outstanding = vault.irrevocable_outstanding.saturating_sub(claimable);
+        }
 
         let mint_key = ctx.accounts.mint.key();
         let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
@@ -223,11 +367,17 @@ pub mod shorthusk_vesting {
             signer,
         );
         token::transfer(cpi_ctx, claimable)?;
-        emit!(ClaimEvent {
+        ctx.accounts.vault_token_account.reload()?;
+        emit_event!(ctx, ClaimEvent {
             vesting_account: vesting_account.key(),
             beneficiary: vesting_account.beneficiary,
             amount: claimable,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: current_time,
+            slot: clock.slot,
         });
 
         Ok(())
@@ -240,11 +390,20 @@ pub mod shorthusk_vesting {
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
+        require!(
+            !ctx.accounts.vesting_account.irrevocable,
+            VestingError::Irrevocable
+        );
         ctx.accounts.vesting_account.paused = true;
-        emit!(PauseEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, PauseEvent {
             vesting_account: ctx.accounts.vesting_account.key(),
             admin: ctx.accounts.admin.key(),
             mint: ctx.accounts.mint.key(),
+            claimed_amount: ctx.accounts.vesting_account.claimed_amount,
+            total_amount: ctx.accounts.vesting_account.total_amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
@@ -257,22 +416,31 @@ pub mod shorthusk_vesting {
             VestingError::Unauthorized
         );
         ctx.accounts.vesting_account.paused = false;
-        emit!(UnpauseEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, UnpauseEvent {
             vesting_account: ctx.accounts.vesting_account.key(),
             admin: ctx.accounts.admin.key(),
             mint: ctx.accounts.mint.key(),
+            claimed_amount: ctx.accounts.vesting_account.claimed_amount,
+            total_amount: ctx.accounts.vesting_account.total_amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
-    /// Pauses the entire vault, preventing all claims. Only callable by the admin.
+    /// Pauses the entire vault, preventing claims from every grant except irrevocable ones. Only callable by the
+    /// admin.
     pub fn pause_vault(ctx: Context<PauseVault>) -> Result<()> {
         require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.vault.admin);
         ctx.accounts.vault.paused = true;
-        emit!(PauseVaultEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, PauseVaultEvent {
             vault: ctx.accounts.vault.key(),
             admin: ctx.accounts.admin.key(),
             mint: ctx.accounts.mint.key(),
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
@@ -281,10 +449,13 @@ pub mod shorthusk_vesting {
     pub fn unpause_vault(ctx: Context<PauseVault>) -> Result<()> {
         require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.vault.admin);
         ctx.accounts.vault.paused = false;
-        emit!(UnpauseVaultEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, UnpauseVaultEvent {
             vault: ctx.accounts.vault.key(),
             admin: ctx.accounts.admin.key(),
             mint: ctx.accounts.mint.key(),
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
@@ -297,6 +468,7 @@ pub mod shorthusk_vesting {
             VestingError::Unauthorized
         );
         let vesting_account = &mut ctx.accounts.vesting_account;
+        require!(!vesting_account.irrevocable, VestingError::Irrevocable);
         let remaining = vesting_account
             .total_amount
             .checked_sub(vesting_account.claimed_amount)
@@ -316,114 +488,209 @@ pub mod shorthusk_vesting {
             signer,
         );
         token::transfer(cpi_ctx, remaining)?;
+        let was_active = vesting_account.is_active();
         vesting_account.total_amount = vesting_account.claimed_amount;
         vesting_account.revoked = true;
-        emit!(RevokeVestingEvent {
+        let vault = &mut ctx.accounts.vault;
+        vault.record_release(remaining);
+        vault.record_activity(was_active, false);
+        ctx.accounts.vault_token_account.reload()?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, RevokeVestingEvent {
             vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
             admin: ctx.accounts.admin.key(),
             remaining_amount: remaining,
+            beneficiary_amount: 0,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
-    /// Instantly unlocks all remaining tokens in a vesting account, transferring them to the beneficiary. Only callable by the admin.
-    pub fn instant_unlock(ctx: Context<InstantUnlock>) -> Result<()> {
+    /// Revokes a vesting account as a good leaver: tokens vested but not yet claimed are paid to the
+    /// beneficiary, and only the unvested remainder is transferred to the recovery destination. Like
+    /// `revoke_vesting`, it also works on paused grants and in a paused vault. Only callable by the admin.
+    pub fn revoke_vesting_good_leaver(ctx: Context<RevokeVestingGoodLeaver>) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
         let vesting_account = &mut ctx.accounts.vesting_account;
+        let clock = Clock::get()?;
 
         require!(vesting_account.initialized, VestingError::NotInitialized);
         require!(!vesting_account.revoked, VestingError::VestingRevoked);
-        require!(!vesting_account.paused, VestingError::Paused);
-        require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        require!(!vesting_account.irrevocable, VestingError::Irrevocable);
 
+        let vested_amount = vesting_account.vested_amount(clock.unix_timestamp)?;
+        let beneficiary_amount = vested_amount.saturating_sub(vesting_account.claimed_amount);
+        let new_claimed = vesting_account
+            .claimed_amount
+            .checked_add(beneficiary_amount)
+            .ok_or(VestingError::InvalidAmount)?;
         let remaining = vesting_account
             .total_amount
-            .checked_sub(vesting_account.claimed_amount)
-            .unwrap();
-        require!(remaining > 0, VestingError::NothingToClaim);
-
-        let new_claimed = vesting_account.claimed_amount.checked_add(remaining)
+            .checked_sub(new_claimed)
             .ok_or(VestingError::InvalidAmount)?;
-        require!(
-            new_claimed <= vesting_account.total_amount,
-            VestingError::InvalidAmount
-        );
 
         let mint_key = ctx.accounts.mint.key();
         let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
         let signer = &[&seeds[..]];
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                to: ctx.accounts.beneficiary_token_account.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            signer,
-        );
-        token::transfer(cpi_ctx, remaining)?;
+        if beneficiary_amount > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, beneficiary_amount)?;
+        }
 
-        vesting_account.claimed_amount = vesting_account.total_amount;
-        emit!(InstantUnlockEvent {
+        if remaining > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recovery_destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, remaining)?;
+        }
+
+        let was_active = vesting_account.is_active();
+        vesting_account.claimed_amount = new_claimed;
+        vesting_account.total_amount = new_claimed;
+        vesting_account.revoked = true;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_payout(beneficiary_amount)?;
+        vault.record_release(remaining);
+        vault.record_activity(was_active, false);
+        ctx.accounts.vault_token_account.reload()?;
+        emit_event!(ctx, RevokeVestingEvent {
             vesting_account: vesting_account.key(),
             beneficiary: vesting_account.beneficiary,
             admin: ctx.accounts.admin.key(),
-            amount: remaining,
+            remaining_amount: remaining,
+            beneficiary_amount,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
-    /// Returns the amount of tokens currently claimable from a vesting account based on the vesting schedule.
-    pub fn get_claimable(ctx: Context<GetClaimable>) -> Result<u64> {
-        let vesting_account = &ctx.accounts.vesting_account;
-        let clock = Clock::get()?;
+    /// Terminates a vesting account at `end_time`, which may be in the past or the future but not before the
+    /// grant's start. The beneficiary can still claim what vested up to `end_time`; the unvested remainder is
+    /// transferred to the recovery destination. Only callable by the admin.
+    pub fn terminate_vesting(ctx: Context<RevokeVesting>, end_time: i64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let vesting_account = &mut ctx.accounts.vesting_account;
 
         require!(vesting_account.initialized, VestingError::NotInitialized);
         require!(!vesting_account.revoked, VestingError::VestingRevoked);
-        require!(!vesting_account.paused, VestingError::Paused);
-        require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        require!(!vesting_account.irrevocable, VestingError::Irrevocable);
+        require!(
+            vesting_account.terminated_at == 0,
+            VestingError::AlreadyTerminated
+        );
+        // `terminated_at == 0` means "not terminated", so the end time must never be stored as zero.
+        require!(
+            end_time > 0 && end_time >= vesting_account.start_time,
+            VestingError::InvalidEndTime
+        );
 
-        let elapsed = clock.unix_timestamp - vesting_account.start_time;
-        require!(elapsed >= 0, VestingError::InvalidTimestamp);
+        // Tokens already claimed are never clawed back, even if `end_time` predates the claim.
+        let vested_at_end = vesting_account
+            .vested_amount(end_time)?
+            .max(vesting_account.claimed_amount);
+        let remaining = vesting_account
+            .total_amount
+            .checked_sub(vested_at_end)
+            .ok_or(VestingError::InvalidAmount)?;
 
-        if elapsed <= vesting_account.cliff_period {
-            return Ok(0); // Nothing claimable before the cliff
+        if remaining > 0 {
+            let mint_key = ctx.accounts.mint.key();
+            let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+            let signer = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recovery_destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, remaining)?;
         }
 
-        let vested_amount = if elapsed >= vesting_account.duration {
-            vesting_account.total_amount
-        } else {
-            vesting_account
-                .total_amount
-                .checked_mul(elapsed as u64)
-                .unwrap()
-                .checked_div(vesting_account.duration as u64)
-                .unwrap()
-        };
-
-        let claimable = vested_amount
-            .checked_sub(vesting_account.claimed_amount)
-            .unwrap_or(0);
-
-        Ok(claimable)
+        let was_active = vesting_account.is_active();
+        vesting_account.total_amount = vested_at_end;
+        vesting_account.terminated_at = end_time;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_release(remaining);
+        vault.record_activity(was_active, vesting_account.is_active());
+        ctx.accounts.vault_token_account.reload()?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, TerminateVestingEvent {
+            vesting_account: vesting_account.key(),
+            admin: ctx.accounts.admin.key(),
+            end_time,
+            vested_amount: vested_at_end,
+            remaining_amount: remaining,
+            mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
     }
 
-    /// Recovers all tokens from the vault to a recovery destination in an emergency. Only callable by the admin.
-    pub fn emergency_recover(ctx: Context<EmergencyRecover>) -> Result<()> {
+    /// Reduces a grant to `new_total` without changing its release rate, transferring the difference to the
+    /// recovery destination. The new total can never be lower than the amount already vested. Only callable by the admin.
+    pub fn reduce_grant(ctx: Context<RevokeVesting>, new_total: u64) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
-        let amount = ctx.accounts.vault_token_account.amount;
-        require!(amount > 0, VestingError::NothingToClaim);
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        let clock = Clock::get()?;
+
+        require!(vesting_account.initialized, VestingError::NotInitialized);
+        require!(!vesting_account.revoked, VestingError::VestingRevoked);
+        require!(!vesting_account.irrevocable, VestingError::Irrevocable);
+
+        let vested_amount = vesting_account
+            .vested_amount(clock.unix_timestamp)?
+            .max(vesting_account.claimed_amount);
+        require!(
+            new_total < vesting_account.total_amount && new_total >= vested_amount,
+            VestingError::InvalidGrantReduction
+        );
+        let reduction = vesting_account.total_amount - new_total;
 
         let mint_key = ctx.accounts.mint.key();
         let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
@@ -438,69 +705,499 @@ pub mod shorthusk_vesting {
             },
             signer,
         );
-        token::transfer(cpi_ctx, amount)?;
-        emit!(EmergencyRecoverEvent {
-            vault: ctx.accounts.vault.key(),
+        token::transfer(cpi_ctx, reduction)?;
+
+        let old_total = vesting_account.total_amount;
+        let was_active = vesting_account.is_active();
+        vesting_account.total_amount = new_total;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_release(reduction);
+        vault.record_activity(was_active, vesting_account.is_active());
+        ctx.accounts.vault_token_account.reload()?;
+        emit_event!(ctx, ReduceGrantEvent {
+            vesting_account: vesting_account.key(),
             admin: ctx.accounts.admin.key(),
-            amount,
+            old_total,
+            new_total,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
-    /// Funds the vault with existing tokens from the admin's token account. Only callable by the admin.
-    pub fn fund_vault_existing(ctx: Context<FundVaultExisting>, amount: u64) -> Result<()> {
-        require!(amount > 0, VestingError::InvalidAmount);
+    /// Amends a live grant by topping up its total and/or extending its duration. Claimed amounts are preserved,
+    /// top-ups vest on the grant's existing curve, and an amendment may never lower the amount already vested.
+    /// The top-up is transferred from the admin's `source_token_account` into the vault, so an amendment never
+    /// promises more than the vault holds. The duration of irrevocable grants cannot be extended. Only callable
+    /// by the admin.
+    ///
+    /// The schedule is a single line from the start time, so a longer duration flattens it and lowers what has
+    /// vested so far: past the cliff, an extension fails with `AmendmentReducesVested` unless it comes with a
+    /// top-up of at least `schedule_amount * (new_duration - duration) / duration` (rounded up), which keeps the
+    /// curve at least as steep. Extend a grant without a top-up before its cliff ends.
+    pub fn amend_vesting(ctx: Context<AmendVesting>, new_total: u64, new_duration: i64) -> Result<()> {
         require_keys_eq!(
             ctx.accounts.admin.key(),
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
-        let source = &ctx.accounts.source_token_account;
-        let payer = &ctx.accounts.payer;
-        require_keys_eq!(source.owner, payer.key(), VestingError::Unauthorized);
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: source.to_account_info(),
-                to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: payer.to_account_info(),
-            },
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        let clock = Clock::get()?;
+
+        require!(vesting_account.initialized, VestingError::NotInitialized);
+        require!(!vesting_account.revoked, VestingError::VestingRevoked);
+        require!(
+            vesting_account.terminated_at == 0,
+            VestingError::AlreadyTerminated
         );
-        token::transfer(cpi_ctx, amount)?;
-        emit!(FundVaultExistingEvent {
-            vault: ctx.accounts.vault.key(),
+        require!(
+            new_total >= vesting_account.total_amount && new_duration >= vesting_account.duration,
+            VestingError::InvalidAmendment
+        );
+        require!(
+            new_total > vesting_account.total_amount || new_duration > vesting_account.duration,
+            VestingError::InvalidAmendment
+        );
+        if vesting_account.irrevocable {
+            require!(
+                new_duration == vesting_account.duration,
+                VestingError::Irrevocable
+            );
+        }
+
+        let vested_before = vesting_account.vested_amount(clock.unix_timestamp)?;
+        let was_active = vesting_account.is_active();
+        let top_up = new_total - vesting_account.total_amount;
+        let old_total = vesting_account.total_amount;
+        let old_duration = vesting_account.duration;
+
+        vesting_account.schedule_amount = vesting_account
+            .schedule_amount
+            .checked_add(top_up)
+            .ok_or(VestingError::InvalidAmount)?;
+        vesting_account.total_amount = new_total;
+        vesting_account.duration = new_duration;
+
+        require!(
+            vesting_account.vested_amount(clock.unix_timestamp)? >= vested_before,
+            VestingError::AmendmentReducesVested
+        );
+
+        if top_up > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx, top_up)?;
+        }
+        ctx.accounts.vault_token_account.reload()?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.record_top_up(top_up)?;
+        vault.record_activity(was_active, vesting_account.is_active());
+        if vesting_account.irrevocable {
+            vault.irrevocable_outstanding = vault
+                .irrevocable_outstanding
+                .checked_add(top_up)
+                .ok_or(VestingError::InvalidAmount)?;
+        }
+
+        emit_event!(ctx, VestingAmendedEvent {
+            vesting_account: vesting_account.key(),
             admin: ctx.accounts.admin.key(),
-            amount,
+            old_total,
+            new_total,
+            old_duration,
+            new_duration,
             mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
-    /// Batch initializes multiple vesting accounts in a single transaction. Only callable by the admin.
-    pub fn batch_initialize_vesting<'info>(
-        ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
-        args: Vec<BatchVestingArgs>,
-    ) -> Result<()> {
+    /// Splits `amount` of a grant's total into a new vesting account for `new_beneficiary`. The claimed and
+    /// scheduled amounts are carved out proportionally and the new grant keeps the same schedule.
+    /// Requires both the admin and the current beneficiary to sign.
+    pub fn split_vesting(ctx: Context<SplitVesting>, amount: u64, new_beneficiary: Pubkey) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let source = &mut ctx.accounts.vesting_account;
+
+        require!(source.initialized, VestingError::NotInitialized);
+        require!(!source.revoked, VestingError::VestingRevoked);
+        require!(!source.paused, VestingError::Paused);
         require!(
-            ctx.accounts.vault.initialized,
-            VestingError::VaultNotInitialized
+            amount > 0 && amount < source.total_amount,
+            VestingError::InvalidAmount
         );
-        require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.vault.admin);
-        require!(args.len() <= 3, VestingError::TooManyAccounts);
 
-        // Validate that all args use the same mint
-        for arg in &args {
-            require_keys_eq!(
-                arg.mint,
-                ctx.accounts.mint.key(),
-                VestingError::InvalidAccount // Reuse existing error
-            );
-        }
+        let proportional = |value: u64| -> Result<u64> {
+            let slice = (value as u128)
+                .checked_mul(amount as u128)
+                .ok_or(VestingError::InvalidAmount)?
+                .checked_div(source.total_amount as u128)
+                .ok_or(VestingError::InvalidAmount)?;
+            Ok(slice as u64)
+        };
+        let claimed_slice = proportional(source.claimed_amount)?;
+        let schedule_slice = proportional(source.schedule_amount)?;
+        let was_active = source.is_active();
+
+        let new_account = &mut ctx.accounts.new_vesting_account;
+        new_account.beneficiary = new_beneficiary;
+        new_account.mint = source.mint;
+        new_account.start_time = source.start_time;
+        new_account.cliff_period = source.cliff_period;
+        new_account.duration = source.duration;
+        new_account.total_amount = amount;
+        new_account.claimed_amount = claimed_slice;
+        new_account.paused = false;
+        new_account.initialized = true;
+        new_account.revoked = false;
+        new_account.bump = ctx.bumps.new_vesting_account;
+        new_account.schedule_amount = schedule_slice;
+        new_account.terminated_at = source.terminated_at;
+        new_account.irrevocable = source.irrevocable;
+
+        source.total_amount -= amount;
+        source.claimed_amount -= claimed_slice;
+        source.schedule_amount -= schedule_slice;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_activity(was_active, source.is_active());
+        vault.record_activity(false, new_account.is_active());
+
+        let clock = Clock::get()?;
+        emit_event!(ctx, VestingSplitEvent {
+            vesting_account: source.key(),
+            new_vesting_account: new_account.key(),
+            beneficiary: source.beneficiary,
+            new_beneficiary,
+            amount,
+            mint: ctx.accounts.mint.key(),
+            claimed_amount: source.claimed_amount,
+            total_amount: source.total_amount,
+            new_claimed_amount: new_account.claimed_amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Merges compatible vesting accounts passed in remaining accounts into `vesting_account`, summing their
+    /// totals and claimed amounts and closing them to the beneficiary, who paid the rent of grants created by
+    /// `claim_merkle`. Sources must share the beneficiary, mint and schedule parameters of the destination.
+    /// Closing a Merkle-created source is safe: its claim receipt stays behind, so the leaf cannot recreate the
+    /// grant. Only callable by the admin.
+    pub fn merge_vesting<'info>(ctx: Context<'_, '_, 'info, 'info, MergeVesting<'info>>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            VestingError::NotEnoughAccounts
+        );
+        let destination = &mut ctx.accounts.vesting_account;
+        require!(destination.is_mergeable(), VestingError::IncompatibleVesting);
+        let vault = &mut ctx.accounts.vault;
+        let was_active = destination.is_active();
+        let clock = Clock::get()?;
+
+        let mut events = Vec::with_capacity(ctx.remaining_accounts.len());
+        for source_info in ctx.remaining_accounts.iter() {
+            require_keys_neq!(
+                source_info.key(),
+                destination.key(),
+                VestingError::InvalidAccount
+            );
+            require!(source_info.is_writable, VestingError::InvalidAccount);
+            let source = Account::<VestingAccount>::try_from(source_info)?;
+            require!(
+                source.is_mergeable()
+                    && source.beneficiary == destination.beneficiary
+                    && source.mint == destination.mint
+                    && source.start_time == destination.start_time
+                    && source.cliff_period == destination.cliff_period
+                    && source.duration == destination.duration
+                    && source.irrevocable == destination.irrevocable,
+                VestingError::IncompatibleVesting
+            );
+
+            destination.total_amount = destination
+                .total_amount
+                .checked_add(source.total_amount)
+                .ok_or(VestingError::InvalidAmount)?;
+            destination.schedule_amount = destination
+                .schedule_amount
+                .checked_add(source.schedule_amount)
+                .ok_or(VestingError::InvalidAmount)?;
+            destination.claimed_amount = destination
+                .claimed_amount
+                .checked_add(source.claimed_amount)
+                .ok_or(VestingError::InvalidAmount)?;
+
+            vault.record_activity(source.is_active(), false);
+            source.close(ctx.accounts.beneficiary.to_account_info())?;
+            events.push(VestingMergedEvent {
+                vesting_account: destination.key(),
+                merged_account: source_info.key(),
+                amount: source.total_amount,
+                mint: ctx.accounts.mint.key(),
+                claimed_amount: destination.claimed_amount,
+                total_amount: destination.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+        vault.record_activity(was_active, destination.is_active());
+        for event in events {
+            emit_event!(ctx, event);
+        }
+        Ok(())
+    }
+
+    /// Instantly unlocks all remaining tokens in a vesting account, transferring them to the beneficiary. Only callable by the admin.
+    pub fn instant_unlock(ctx: Context<InstantUnlock>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let vesting_account = &mut ctx.accounts.vesting_account;
+
+        require!(vesting_account.initialized, VestingError::NotInitialized);
+        require!(!vesting_account.revoked, VestingError::VestingRevoked);
+        require!(!vesting_account.paused, VestingError::Paused);
+        require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        if vesting_account.irrevocable {
+            require_keys_eq!(
+                ctx.accounts.beneficiary_token_account.owner,
+                vesting_account.beneficiary,
+                VestingError::InvalidAccount
+            );
+        }
+
+        let remaining = vesting_account
+            .total_amount
+            .checked_sub(vesting_account.claimed_amount)
+            .unwrap();
+        require!(remaining > 0, VestingError::NothingToClaim);
+
+        let new_claimed = vesting_account.claimed_amount.checked_add(remaining)
+            .ok_or(VestingError::InvalidAmount)?;
+        require!(
+            new_claimed <= vesting_account.total_amount,
+            VestingError::InvalidAmount
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(cpi_ctx, remaining)?;
+
+        vesting_account.claimed_amount = vesting_account.total_amount;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_payout(remaining)?;
+        vault.record_activity(true, false);
+        if vesting_account.irrevocable {
+            vault.irrevocable_outstanding = vault.irrevocable_outstanding.saturating_sub(remaining);
+        }
+        ctx.accounts.vault_token_account.reload()?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, InstantUnlockEvent {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            admin: ctx.accounts.admin.key(),
+            amount: remaining,
+            mint: ctx.accounts.mint.key(),
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Returns the amount of tokens currently claimable from a vesting account based on the vesting schedule.
+    pub fn get_claimable(ctx: Context<GetClaimable>) -> Result<u64> {
+        let vesting_account = &ctx.accounts.vesting_account;
+        let clock = Clock::get()?;
+
+        require!(vesting_account.initialized, VestingError::NotInitialized);
+        require!(!vesting_account.revoked, VestingError::VestingRevoked);
+        require!(!vesting_account.paused, VestingError::Paused);
+        require!(
+            !vesting_account.blocked_by_vault_pause(ctx.accounts.vault.paused),
+            VestingError::VaultPaused
+        );
+
+        let elapsed = clock.unix_timestamp - vesting_account.start_time;
+        require!(elapsed >= 0, VestingError::InvalidTimestamp);
+
+        let vested_amount = vesting_account.vested_amount(clock.unix_timestamp)?;
+
+        let claimable = vested_amount.saturating_sub(vesting_account.claimed_amount);
+
+        Ok(claimable)
+    }
+
+    /// Returns the full state of a vesting account as of now. Unlike `get_claimable` it does not fail for
+    /// paused or revoked grants, and works for grants created from a Merkle distribution as well.
+    pub fn get_vesting_status(ctx: Context<GetVestingStatus>) -> Result<VestingStatus> {
+        let clock = Clock::get()?;
+        ctx.accounts
+            .vesting_account
+            .status(clock.unix_timestamp, ctx.accounts.vault.paused)
+    }
+
+    /// Returns the vault's admin, pause state, token balance and grant totals, as tracked by the vault.
+    pub fn get_vault_summary(ctx: Context<GetVaultSummary>) -> Result<VaultSummary> {
+        let vault = &ctx.accounts.vault;
+        let vault_balance = ctx.accounts.vault_token_account.amount;
+        Ok(VaultSummary {
+            admin: vault.admin,
+            paused: vault.paused,
+            vault_balance,
+            total_committed: vault.total_committed,
+            total_claimed: vault.total_claimed,
+            active_grants: vault.active_grants,
+            distribution_reserved: vault.distribution_reserved,
+            surplus: vault.surplus(vault_balance),
+        })
+    }
+
+    /// Recovers all tokens from the vault to a recovery destination in an emergency, except those still owed
+    /// to irrevocable grants. Only callable by the admin.
+    pub fn emergency_recover(ctx: Context<EmergencyRecover>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let amount = ctx
+            .accounts
+            .vault_token_account
+            .amount
+            .saturating_sub(ctx.accounts.vault.irrevocable_outstanding);
+        require!(amount > 0, VestingError::NothingToClaim);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.recovery_destination.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.vault_token_account.reload()?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, EmergencyRecoverEvent {
+            vault: ctx.accounts.vault.key(),
+            admin: ctx.accounts.admin.key(),
+            amount,
+            mint: ctx.accounts.mint.key(),
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Funds the vault with existing tokens from the admin's token account. Only callable by the admin.
+    pub fn fund_vault_existing(ctx: Context<FundVaultExisting>, amount: u64) -> Result<()> {
+        require!(amount > 0, VestingError::InvalidAmount);
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let source = &ctx.accounts.source_token_account;
+        let payer = &ctx.accounts.payer;
+        require_keys_eq!(source.owner, payer.key(), VestingError::Unauthorized);
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: source.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: payer.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.vault_token_account.reload()?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, FundVaultExistingEvent {
+            vault: ctx.accounts.vault.key(),
+            admin: ctx.accounts.admin.key(),
+            amount,
+            mint: ctx.accounts.mint.key(),
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Batch initializes multiple vesting accounts in a single transaction. Only callable by the admin.
+    ///
+    /// Deprecated: use `batch_initialize_vesting_v2`. Only built with the `legacy-batch` feature.
+    #[cfg(feature = "legacy-batch")]
+    pub fn batch_initialize_vesting<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
+        args: Vec<BatchVestingArgs>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.vault.initialized,
+            VestingError::VaultNotInitialized
+        );
+        require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.vault.admin);
+        require!(args.len() <= 3, VestingError::TooManyAccounts);
+
+        // Validate that all args use the same mint
+        for arg in &args {
+            require_keys_eq!(
+                arg.mint,
+                ctx.accounts.mint.key(),
+                VestingError::InvalidAccount // Reuse existing error
+            );
+        }
 
         let admin = ctx.accounts.admin.to_account_info();
         let system_program = ctx.accounts.system_program.to_account_info();
         let vault = ctx.accounts.vault.key();
+        let clock = Clock::get()?;
 
         let mut successful_inits = 0;
         for (i, arg) in args.iter().enumerate() {
@@ -511,16 +1208,20 @@ pub mod shorthusk_vesting {
                 ctx.program_id,
                 &admin,
                 &system_program,
-                &ctx.remaining_accounts,
+                ctx.remaining_accounts,
             ) {
                 Ok(vesting_account_key) => {
                     successful_inits += 1;
-                    emit!(BatchInitializeVestingEvent {
+                    ctx.accounts.vault.record_grant(arg.total_amount)?;
+                    emit_event!(ctx, BatchInitializeVestingEvent {
                         vault,
                         admin: admin.key(),
                         num_accounts: 1,
                         vesting_account: vesting_account_key,
                         mint: ctx.accounts.mint.key(),
+                        total_amount: arg.total_amount,
+                        timestamp: clock.unix_timestamp,
+                        slot: clock.slot,
                     });
                 }
                 Err(e) => {
@@ -538,10 +1239,34 @@ pub mod shorthusk_vesting {
     }
 
     /// Batch initializes multiple vesting accounts (v2) in a single transaction using remaining accounts.
-    /// Only callable by the admin. Supports up to 3 vesting accounts.
+    /// Remaining accounts hold a (vesting PDA, beneficiary) pair per entry. This is the canonical batch
+    /// instruction. Only callable by the admin. Supports up to 3 vesting accounts.
     pub fn batch_initialize_vesting_v2<'info>(
         ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
         args: Vec<BatchVestingArgs>,
+    ) -> Result<()> {
+        batch_initialize(ctx, args, false)
+    }
+
+    /// Idempotent variant of `batch_initialize_vesting_v2`: entries whose vesting account already exists with
+    /// identical parameters are skipped instead of failing, so the same batch can safely be sent again. Existing
+    /// accounts with different parameters still fail the transaction. Emits a `BatchItemResultEvent` per entry.
+    /// Only callable by the admin. Supports up to 3 vesting accounts.
+    pub fn batch_initialize_vesting_idempotent<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
+        args: Vec<BatchVestingArgs>,
+    ) -> Result<()> {
+        batch_initialize(ctx, args, true)
+    }
+
+    /// Batch initializes vesting accounts that share one schedule, taking only a beneficiary, amount and canonical
+    /// PDA bump per entry. Remaining accounts hold one vesting PDA per entry, in order. There is no fixed entry
+    /// limit: a legacy transaction fits 10 entries, so larger batches need address lookup tables. Only callable
+    /// by the admin.
+    pub fn batch_initialize_vesting_compact<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
+        schedule: BatchScheduleArgs,
+        entries: Vec<BatchVestingEntry>,
     ) -> Result<()> {
         require!(
             ctx.accounts.vault.initialized,
@@ -552,142 +1277,332 @@ pub mod shorthusk_vesting {
             ctx.accounts.vault.admin,
             VestingError::Unauthorized
         );
-        require!(!args.is_empty(), VestingError::NotEnoughAccounts);
-        require!(args.len() <= 3, VestingError::TooManyAccounts);
+        require!(!entries.is_empty(), VestingError::NotEnoughAccounts);
         require!(
-            ctx.remaining_accounts.len() == args.len() * 2,
+            ctx.remaining_accounts.len() == entries.len(),
             VestingError::InvalidAccountCount
         );
 
-        // Validate that all args use the same mint
-        for arg in &args {
-            require_keys_eq!(
-                arg.mint,
-                ctx.accounts.mint.key(),
-                VestingError::InvalidAccount
-            );
-        }
-
         let admin = ctx.accounts.admin.to_account_info();
         let system_program = ctx.accounts.system_program.to_account_info();
         let vault = ctx.accounts.vault.key();
+        let mint_key = ctx.accounts.mint.key();
+        let clock = Clock::get()?;
 
-        for (i, arg) in args.iter().enumerate() {
-            let vesting_account_idx = i * 2;
-            let beneficiary_idx = i * 2 + 1;
-
-            let vesting_account_info = ctx.remaining_accounts
-                .get(vesting_account_idx)
-                .ok_or(VestingError::NotEnoughAccounts)?;
-            let beneficiary_info = ctx.remaining_accounts
-                .get(beneficiary_idx)
-                .ok_or(VestingError::NotEnoughAccounts)?;
+        for (entry, account_info) in entries.iter().zip(ctx.remaining_accounts.iter()) {
+            validate_schedule(schedule.cliff_period, schedule.duration, entry.total_amount)?;
 
-            require_keys_eq!(
-                beneficiary_info.key(),
-                arg.beneficiary,
-                VestingError::InvalidAccount
-            );
-
-            let mint_key = ctx.accounts.mint.key();
-            let (expected_pda, bump) = Pubkey::find_program_address(
-                &[b"vesting", vault.as_ref(), mint_key.as_ref(), arg.beneficiary.as_ref()],
-                ctx.program_id,
+            // The bump comes from the client, so one hash per higher bump replaces the search of
+            // `find_program_address`. A non-canonical bump would let the same grant live at a second PDA.
+            let seeds: &[&[u8]] = &[
+                b"vesting",
+                vault.as_ref(),
+                mint_key.as_ref(),
+                entry.beneficiary.as_ref(),
+                &[entry.bump],
+            ];
+            require!(
+                is_canonical_bump(&seeds[..4], entry.bump, ctx.program_id),
+                VestingError::NonCanonicalBump
             );
+            let expected_pda = Pubkey::create_program_address(seeds, ctx.program_id)
+                .map_err(|_| VestingError::InvalidAccount)?;
             require_keys_eq!(
-                vesting_account_info.key(),
+                account_info.key(),
                 expected_pda,
                 VestingError::InvalidAccount
             );
 
-            let account_info = vesting_account_info;
-            let is_initialized = {
-                let account_data = account_info.try_borrow_data()?;
-                if account_data.len() > 0 {
-                    let mut data_slice: &[u8] = &account_data;
-                    if let Ok(existing) = VestingAccount::try_deserialize(&mut data_slice) {
-                        existing.initialized
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            };
+            create_vesting_account(
+                account_info,
+                &admin,
+                &system_program,
+                ctx.program_id,
+                seeds,
+                &VestingAccount::new(
+                    entry.beneficiary,
+                    mint_key,
+                    schedule.start_time,
+                    schedule.cliff_period,
+                    schedule.duration,
+                    entry.total_amount,
+                    entry.bump,
+                    false,
+                ),
+            )?;
+            ctx.accounts.vault.record_grant(entry.total_amount)?;
 
-            require!(
-                !is_initialized,
-                VestingError::AlreadyInitialized
-            );
+            emit_event!(ctx, BatchInitializeVestingEvent {
+                vault,
+                admin: admin.key(),
+                num_accounts: 1,
+                vesting_account: account_info.key(),
+                mint: mint_key,
+                total_amount: entry.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
 
-            require_eq!(
-                account_info.owner,
-                &System::id(),
-                VestingError::InvalidAccountOwner
-            );
+        Ok(())
+    }
 
-            let space = 8 + VestingAccount::LEN;
-            let rent = Rent::get()?.minimum_balance(space);
+    /// Pauses every vesting account passed in `remaining_accounts`. Each account must be writable, live at the
+    /// vault's standard vesting PDA for its beneficiary and be revocable. Emits a `PauseEvent` per account. Only callable by the admin.
+    pub fn batch_pause<'info>(ctx: Context<'_, '_, 'info, 'info, BatchAdminAction<'info>>) -> Result<()> {
+        batch_set_paused(ctx, true)
+    }
 
-            let ix = anchor_lang::solana_program::system_instruction::create_account(
-                admin.key,
-                account_info.key,
-                rent,
-                space as u64,
-                ctx.program_id,
-            );
+    /// Unpauses every vesting account passed in `remaining_accounts`. Emits an `UnpauseEvent` per account.
+    /// Only callable by the admin.
+    pub fn batch_unpause<'info>(ctx: Context<'_, '_, 'info, 'info, BatchAdminAction<'info>>) -> Result<()> {
+        batch_set_paused(ctx, false)
+    }
 
-            let seeds = &[
-                b"vesting".as_ref(),
-                vault.as_ref(),
-                mint_key.as_ref(),
-                arg.beneficiary.as_ref(),
-                &[bump],
-            ];
-            let signer_seeds = &[&seeds[..]];
-
-            anchor_lang::solana_program::program::invoke_signed(
-                &ix,
-                &[
-                    admin.clone(),
-                    account_info.clone(),
-                    system_program.clone(),
-                ],
-                signer_seeds,
-            )?;
+    /// Revokes every vesting account passed in `remaining_accounts`, transferring each one's unclaimed tokens to
+    /// the recovery destination. Fails if any account is irrevocable or already revoked. Emits a
+    /// `RevokeVestingEvent` per account. Only callable by the admin.
+    pub fn batch_revoke_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchRevokeVesting<'info>>,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            VestingError::NotEnoughAccounts
+        );
 
-            let vesting_account = VestingAccount {
-                beneficiary: arg.beneficiary,
+        let vault_key = ctx.accounts.vault.key();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer = &[&seeds[..]];
+        let clock = Clock::get()?;
+
+        for vesting_info in ctx.remaining_accounts.iter() {
+            let mut vesting_account =
+                load_batch_vesting_account(vesting_info, &vault_key, &mint_key, ctx.program_id)?;
+            require!(!vesting_account.irrevocable, VestingError::Irrevocable);
+            require!(!vesting_account.revoked, VestingError::VestingRevoked);
+            let remaining = vesting_account
+                .total_amount
+                .checked_sub(vesting_account.claimed_amount)
+                .ok_or(VestingError::InvalidAmount)?;
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recovery_destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, remaining)?;
+            let was_active = vesting_account.is_active();
+            vesting_account.total_amount = vesting_account.claimed_amount;
+            vesting_account.revoked = true;
+            vesting_account.exit(ctx.program_id)?;
+            ctx.accounts.vault.record_release(remaining);
+            ctx.accounts.vault.record_activity(was_active, false);
+            ctx.accounts.vault_token_account.reload()?;
+
+            emit_event!(ctx, RevokeVestingEvent {
+                vesting_account: vesting_info.key(),
+                beneficiary: vesting_account.beneficiary,
+                admin: ctx.accounts.admin.key(),
+                remaining_amount: remaining,
+                beneficiary_amount: 0,
                 mint: mint_key,
-                start_time: arg.start_time,
-                cliff_period: arg.cliff_period,
-                duration: arg.duration,
-                total_amount: arg.total_amount,
-                claimed_amount: 0,
-                paused: false,
-                initialized: true,
-                revoked: false,
-                bump,
-            };
+                claimed_amount: vesting_account.claimed_amount,
+                total_amount: vesting_account.total_amount,
+                vault_balance: ctx.accounts.vault_token_account.amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+        Ok(())
+    }
+
+    /// Creates a Merkle distribution whose leaves each describe one beneficiary's grant. Grants are created
+    /// lazily by beneficiaries through `claim_merkle`. Only callable by the admin.
+    pub fn initialize_distribution(
+        ctx: Context<InitializeDistribution>,
+        id: u64,
+        root: [u8; 32],
+        total_amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.vault.initialized,
+            VestingError::VaultNotInitialized
+        );
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        require!(total_amount > 0, VestingError::InvalidAmount);
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.bump = ctx.bumps.distribution;
+        distribution.vault = ctx.accounts.vault.key();
+        distribution.mint = ctx.accounts.mint.key();
+        distribution.id = id;
+        distribution.root = root;
+        distribution.total_amount = total_amount;
+        distribution.allocated_amount = 0;
+        distribution.num_grants = 0;
+        ctx.accounts.vault.record_reservation(total_amount)?;
+        let clock = Clock::get()?;
+        emit_event!(ctx, DistributionInitializedEvent {
+            distribution: distribution.key(),
+            vault: distribution.vault,
+            admin: ctx.accounts.admin.key(),
+            root,
+            total_amount,
+            mint: distribution.mint,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Claims vested tokens from a Merkle distribution. The first call verifies `proof` against the
+    /// distribution root and creates the beneficiary's vesting account; later calls only claim. The
+    /// beneficiary's claim receipt keeps the leaf from creating another grant once that account is closed.
+    pub fn claim_merkle(
+        ctx: Context<ClaimMerkle>,
+        amount: u64,
+        start_time: i64,
+        cliff_period: i64,
+        duration: i64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.vault.paused, VestingError::VaultPaused);
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let clock = Clock::get()?;
 
-            require!(arg.duration > 0, VestingError::InvalidDuration);
+        if !ctx.accounts.vesting_account.initialized {
+            let distribution = &mut ctx.accounts.distribution;
+            let vesting_account = &mut ctx.accounts.vesting_account;
+            let receipt = &mut ctx.accounts.claim_receipt;
+            require!(!receipt.claimed, VestingError::LeafAlreadyClaimed);
+            let leaf = merkle_leaf(&beneficiary, amount, start_time, cliff_period, duration);
             require!(
-                arg.cliff_period >= 0 && arg.cliff_period <= arg.duration,
-                VestingError::InvalidCliffPeriod
+                verify_merkle_proof(&proof, distribution.root, leaf),
+                VestingError::InvalidProof
             );
-            require!(arg.total_amount > 0, VestingError::InvalidAmount);
-
-            vesting_account.try_serialize(&mut &mut account_info.data.borrow_mut()[..])?;
-
-            emit!(BatchInitializeVestingEvent {
-                vault,
-                admin: admin.key(),
-                num_accounts: 1,
-                vesting_account: account_info.key(),
-                mint: ctx.accounts.mint.key(),
+            receipt.bump = ctx.bumps.claim_receipt;
+            receipt.claimed = true;
+            receipt.leaf = leaf;
+            validate_schedule(cliff_period, duration, amount)?;
+
+            let allocated = distribution
+                .allocated_amount
+                .checked_add(amount)
+                .ok_or(VestingError::InvalidAmount)?;
+            require!(
+                allocated <= distribution.total_amount,
+                VestingError::DistributionExhausted
+            );
+            distribution.allocated_amount = allocated;
+            distribution.num_grants += 1;
+
+            vesting_account.set_inner(VestingAccount::new(
+                beneficiary,
+                distribution.mint,
+                start_time,
+                cliff_period,
+                duration,
+                amount,
+                ctx.bumps.vesting_account,
+                false,
+            ));
+            ctx.accounts.vault.record_allocation(amount);
+            ctx.accounts.vault.record_grant(amount)?;
+            emit_event!(ctx, MerkleVestingCreatedEvent {
+                distribution: distribution.key(),
+                vesting_account: vesting_account.key(),
+                beneficiary,
+                total_amount: amount,
+                mint: distribution.mint,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
             });
         }
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        require!(!vesting_account.revoked, VestingError::VestingRevoked);
+        require!(!vesting_account.paused, VestingError::Paused);
+
+        let claimable = vesting_account
+            .vested_amount(clock.unix_timestamp)?
+            .saturating_sub(vesting_account.claimed_amount);
+        if claimable == 0 {
+            return Ok(());
+        }
+        vesting_account.claimed_amount = vesting_account
+            .claimed_amount
+            .checked_add(claimable)
+            .ok_or(VestingError::InvalidAmount)?;
+        let vault = &mut ctx.accounts.vault;
+        vault.record_payout(claimable)?;
+        vault.record_activity(true, vesting_account.is_active());
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(cpi_ctx, claimable)?;
+        ctx.accounts.vault_token_account.reload()?;
+        emit_event!(ctx, ClaimEvent {
+            vesting_account: vesting_account.key(),
+            beneficiary,
+            amount: claimable,
+            mint: mint_key,
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
 
+    /// Closes a Merkle distribution, releasing its unallocated remainder from the vault's reservation and
+    /// returning its rent to the admin. Leaves not yet claimed can no longer create grants; grants already
+    /// created keep vesting and are claimed through `claim_many`. Only callable by the admin.
+    pub fn close_distribution(ctx: Context<CloseDistribution>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        let distribution = &ctx.accounts.distribution;
+        let released_amount = distribution
+            .total_amount
+            .saturating_sub(distribution.allocated_amount);
+        ctx.accounts.vault.release_reservation(released_amount);
+        let clock = Clock::get()?;
+        emit_event!(ctx, DistributionClosedEvent {
+            distribution: distribution.key(),
+            vault: distribution.vault,
+            admin: ctx.accounts.admin.key(),
+            released_amount,
+            num_grants: distribution.num_grants,
+            mint: distribution.mint,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
         Ok(())
     }
 
@@ -700,15 +1615,119 @@ pub mod shorthusk_vesting {
         );
         let old_admin = ctx.accounts.vault.admin;
         ctx.accounts.vault.admin = new_admin;
-        emit!(UpdateAdminEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, UpdateAdminEvent {
             vault: ctx.accounts.vault.key(),
             old_admin,
             new_admin,
             mint: ctx.accounts.mint.key(),
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Brings a vault created by an earlier program version, whose account ends after `initialized`, to the
+    /// current layout. The account is resized, with the admin paying the extra rent, and its grant totals start
+    /// at zero; `migrate_vesting` then adds each of the vault's earlier grants to them. Only callable by the
+    /// admin.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        require_keys_eq!(
+            *vault_info.owner,
+            *ctx.program_id,
+            VestingError::InvalidAccountOwner
+        );
+        require!(
+            vault_info.data_len() != 8 + Vault::LEN,
+            VestingError::AlreadyMigrated
+        );
+        require_eq!(
+            vault_info.data_len(),
+            8 + Vault::V1_LEN,
+            VestingError::InvalidAccount
+        );
+
+        grow_account(
+            &vault_info,
+            8 + Vault::LEN,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        let vault = Vault::try_deserialize(&mut &vault_info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            vault.admin,
+            VestingError::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        emit_event!(ctx, VaultMigratedEvent {
+            vault: vault_info.key(),
+            admin: ctx.accounts.admin.key(),
+            mint: ctx.accounts.mint.key(),
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 
+    /// Brings grants created by an earlier program version, passed in `remaining_accounts`, to the current
+    /// layout. Their accounts end after `bump`; each one is resized, with the admin paying the extra rent, and
+    /// gets `schedule_amount = total_amount`, `terminated_at = 0` and `irrevocable = false`, which leaves its
+    /// schedule as it was. The vault's grant totals never counted these grants, so each is added to them.
+    /// Emits a `VestingMigratedEvent` per account. Only callable by the admin.
+    pub fn migrate_vesting<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateVesting<'info>>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            ctx.accounts.vault.admin,
+            VestingError::Unauthorized
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            VestingError::NotEnoughAccounts
+        );
+
+        let vault_key = ctx.accounts.vault.key();
+        let mint_key = ctx.accounts.mint.key();
+        let admin_info = ctx.accounts.admin.to_account_info();
+        let system_program_info = ctx.accounts.system_program.to_account_info();
+        let clock = Clock::get()?;
+
+        for vesting_info in ctx.remaining_accounts.iter() {
+            require!(vesting_info.is_writable, VestingError::InvalidAccount);
+            require_keys_eq!(
+                *vesting_info.owner,
+                *ctx.program_id,
+                VestingError::InvalidAccountOwner
+            );
+            let vesting_account = VestingAccount::try_deserialize_v1(&vesting_info.try_borrow_data()?)?;
+            require_keys_eq!(vesting_account.mint, mint_key, VestingError::InvalidAccount);
+            check_vesting_pda(vesting_info.key, &vault_key, &vesting_account, ctx.program_id)?;
+
+            grow_account(
+                vesting_info,
+                8 + VestingAccount::LEN,
+                &admin_info,
+                &system_program_info,
+            )?;
+            vesting_account.try_serialize(&mut &mut vesting_info.try_borrow_mut_data()?[..])?;
+            ctx.accounts.vault.record_migrated_grant(&vesting_account)?;
+
+            emit_event!(ctx, VestingMigratedEvent {
+                vesting_account: vesting_info.key(),
+                beneficiary: vesting_account.beneficiary,
+                admin: ctx.accounts.admin.key(),
+                mint: mint_key,
+                claimed_amount: vesting_account.claimed_amount,
+                total_amount: vesting_account.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+        Ok(())
+    }
+
     /// Attempts to re-initialize a vesting account (for testing purposes).
     pub fn reinitialize_vesting(
         ctx: Context<ReinitializeVesting>,
@@ -730,23 +1749,346 @@ pub mod shorthusk_vesting {
         vesting_account.cliff_period = cliff_period;
         vesting_account.duration = duration;
         vesting_account.total_amount = total_amount;
+        vesting_account.schedule_amount = total_amount;
         vesting_account.claimed_amount = 0;
+        vesting_account.terminated_at = 0;
         vesting_account.paused = false;
         vesting_account.revoked = false;
         vesting_account.initialized = true;
         vesting_account.mint = ctx.accounts.mint.key(); // Ensure mint is set
+        // The revoked grant's claimed tokens stay counted as committed and claimed.
+        let vault = &mut ctx.accounts.vault;
+        vault.record_top_up(total_amount)?;
+        vault.record_activity(false, vesting_account.is_active());
 
-        emit!(VestingReinitializedEvent {
+        let clock = Clock::get()?;
+        emit_event!(ctx, VestingReinitializedEvent {
             vesting_account: vesting_account.key(),
             beneficiary: vesting_account.beneficiary,
             mint: ctx.accounts.mint.key(),
             total_amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
         });
         Ok(())
     }
 }
 
+/// Shared implementation of `batch_initialize_vesting_v2` and `batch_initialize_vesting_idempotent`.
+/// With `skip_existing`, entries whose vesting account already holds identical parameters are skipped.
+pub fn batch_initialize<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchInitializeVesting<'info>>,
+    args: Vec<BatchVestingArgs>,
+    skip_existing: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.vault.initialized,
+        VestingError::VaultNotInitialized
+    );
+    require_keys_eq!(
+        ctx.accounts.admin.key(),
+        ctx.accounts.vault.admin,
+        VestingError::Unauthorized
+    );
+    require!(!args.is_empty(), VestingError::NotEnoughAccounts);
+    require!(args.len() <= 3, VestingError::TooManyAccounts);
+    require!(
+        ctx.remaining_accounts.len() == args.len() * 2,
+        VestingError::InvalidAccountCount
+    );
+
+    // Validate that all args use the same mint
+    for arg in &args {
+        require_keys_eq!(
+            arg.mint,
+            ctx.accounts.mint.key(),
+            VestingError::InvalidAccount
+        );
+    }
+
+    let admin = ctx.accounts.admin.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let vault = ctx.accounts.vault.key();
+    let mint_key = ctx.accounts.mint.key();
+    let clock = Clock::get()?;
+
+    for (i, (arg, pair)) in args.iter().zip(ctx.remaining_accounts.chunks(2)).enumerate() {
+        let (vesting_account_info, beneficiary_info) = (&pair[0], &pair[1]);
+
+        require_keys_eq!(
+            beneficiary_info.key(),
+            arg.beneficiary,
+            VestingError::InvalidAccount
+        );
+        validate_schedule(arg.cliff_period, arg.duration, arg.total_amount)?;
+
+        let (expected_pda, bump) = Pubkey::find_program_address(
+            &[b"vesting", vault.as_ref(), mint_key.as_ref(), arg.beneficiary.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            vesting_account_info.key(),
+            expected_pda,
+            VestingError::InvalidAccount
+        );
+
+        if skip_existing && vesting_account_info.owner == ctx.program_id {
+            let existing = {
+                let data = vesting_account_info.try_borrow_data()?;
+                VestingAccount::try_deserialize(&mut &data[..])?
+            };
+            require!(
+                existing.matches(arg),
+                VestingError::ConflictingVestingAccount
+            );
+            emit_event!(ctx, BatchItemResultEvent {
+                vault,
+                index: i as u32,
+                vesting_account: vesting_account_info.key(),
+                beneficiary: arg.beneficiary,
+                status: BatchItemStatus::Skipped,
+                mint: mint_key,
+                claimed_amount: existing.claimed_amount,
+                total_amount: existing.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+            continue;
+        }
+
+        create_vesting_account(
+            vesting_account_info,
+            &admin,
+            &system_program,
+            ctx.program_id,
+            &[
+                b"vesting",
+                vault.as_ref(),
+                mint_key.as_ref(),
+                arg.beneficiary.as_ref(),
+                &[bump],
+            ],
+            &VestingAccount::new(
+                arg.beneficiary,
+                mint_key,
+                arg.start_time,
+                arg.cliff_period,
+                arg.duration,
+                arg.total_amount,
+                bump,
+                false,
+            ),
+        )?;
+        ctx.accounts.vault.record_grant(arg.total_amount)?;
+
+        emit_event!(ctx, BatchInitializeVestingEvent {
+            vault,
+            admin: admin.key(),
+            num_accounts: 1,
+            vesting_account: vesting_account_info.key(),
+            mint: mint_key,
+            total_amount: arg.total_amount,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+        if skip_existing {
+            emit_event!(ctx, BatchItemResultEvent {
+                vault,
+                index: i as u32,
+                vesting_account: vesting_account_info.key(),
+                beneficiary: arg.beneficiary,
+                status: BatchItemStatus::Created,
+                mint: mint_key,
+                claimed_amount: 0,
+                total_amount: arg.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Shared implementation of `batch_pause` and `batch_unpause`.
+pub fn batch_set_paused<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchAdminAction<'info>>,
+    paused: bool,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.admin.key(),
+        ctx.accounts.vault.admin,
+        VestingError::Unauthorized
+    );
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        VestingError::NotEnoughAccounts
+    );
+
+    let vault_key = ctx.accounts.vault.key();
+    let mint_key = ctx.accounts.mint.key();
+    let clock = Clock::get()?;
+    for vesting_info in ctx.remaining_accounts.iter() {
+        let mut vesting_account =
+            load_batch_vesting_account(vesting_info, &vault_key, &mint_key, ctx.program_id)?;
+        if paused {
+            require!(!vesting_account.irrevocable, VestingError::Irrevocable);
+        }
+        vesting_account.paused = paused;
+        vesting_account.exit(ctx.program_id)?;
+
+        if paused {
+            emit_event!(ctx, PauseEvent {
+                vesting_account: vesting_info.key(),
+                admin: ctx.accounts.admin.key(),
+                mint: mint_key,
+                claimed_amount: vesting_account.claimed_amount,
+                total_amount: vesting_account.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        } else {
+            emit_event!(ctx, UnpauseEvent {
+                vesting_account: vesting_info.key(),
+                admin: ctx.accounts.admin.key(),
+                mint: mint_key,
+                claimed_amount: vesting_account.claimed_amount,
+                total_amount: vesting_account.total_amount,
+                timestamp: clock.unix_timestamp,
+                slot: clock.slot,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Loads a vesting account passed in `remaining_accounts` of a batch admin instruction. The account must live at
+/// the `[b"vesting", vault, mint, beneficiary]` PDA, the same seeds the single-account admin instructions check,
+/// so batches cannot act on Merkle-created grants either.
+pub fn load_batch_vesting_account<'info>(
+    vesting_info: &'info AccountInfo<'info>,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Account<'info, VestingAccount>> {
+    require!(vesting_info.is_writable, VestingError::InvalidAccount);
+    let vesting_account = Account::<VestingAccount>::try_from(vesting_info)?;
+    require_keys_eq!(vesting_account.mint, *mint, VestingError::InvalidAccount);
+    check_vesting_pda(vesting_info.key, vault, &vesting_account, program_id)?;
+    require!(vesting_account.initialized, VestingError::NotInitialized);
+    Ok(vesting_account)
+}
+
+/// Checks that `address` is the vault's `[b"vesting", vault, mint, beneficiary]` PDA for `vesting_account`,
+/// using the bump it stores.
+pub fn check_vesting_pda(
+    address: &Pubkey,
+    vault: &Pubkey,
+    vesting_account: &VestingAccount,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let expected_pda = Pubkey::create_program_address(
+        &[
+            b"vesting",
+            vault.as_ref(),
+            vesting_account.mint.as_ref(),
+            vesting_account.beneficiary.as_ref(),
+            &[vesting_account.bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| VestingError::InvalidAccount)?;
+    require_keys_eq!(*address, expected_pda, VestingError::InvalidAccount);
+    Ok(())
+}
+
+/// Resizes a program-owned account to `space` bytes, with `payer` topping up its rent. The added bytes are
+/// zero.
+pub fn grow_account<'info>(
+    account_info: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let top_up = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account_info.lamports());
+    if top_up > 0 {
+        let ix = anchor_lang::solana_program::system_instruction::transfer(payer.key, account_info.key, top_up);
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[payer.clone(), account_info.clone(), system_program.clone()],
+        )?;
+    }
+    account_info.realloc(space, true)?;
+    Ok(())
+}
+
+/// Checks the schedule rules shared by every instruction that creates a vesting account.
+pub fn validate_schedule(cliff_period: i64, duration: i64, total_amount: u64) -> Result<()> {
+    require!(duration > 0, VestingError::InvalidDuration);
+    require!(
+        cliff_period >= 0 && cliff_period <= duration,
+        VestingError::InvalidCliffPeriod
+    );
+    require!(total_amount > 0, VestingError::InvalidAmount);
+    Ok(())
+}
+
+/// Whether `bump` is the canonical bump for `seeds`: every higher bump must give an address on the curve.
+pub fn is_canonical_bump(seeds: &[&[u8]], bump: u8, program_id: &Pubkey) -> bool {
+    (u16::from(bump) + 1..=u16::from(u8::MAX)).all(|higher| {
+        let higher = [higher as u8];
+        let mut seeds = seeds.to_vec();
+        seeds.push(&higher);
+        Pubkey::create_program_address(&seeds, program_id).is_err()
+    })
+}
+
+/// Creates a vesting account at a PDA passed in remaining accounts and writes `vesting_account` to it.
+/// Fails if the PDA is already owned by the program.
+pub fn create_vesting_account<'info>(
+    account_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    signer_seeds: &[&[u8]],
+    vesting_account: &VestingAccount,
+) -> Result<()> {
+    require_keys_neq!(
+        *account_info.owner,
+        *program_id,
+        VestingError::AlreadyInitialized
+    );
+    require_eq!(
+        account_info.owner,
+        &System::id(),
+        VestingError::InvalidAccountOwner
+    );
+
+    let space = 8 + VestingAccount::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+
+    let ix = anchor_lang::solana_program::system_instruction::create_account(
+        payer.key,
+        account_info.key,
+        rent,
+        space as u64,
+        program_id,
+    );
+
+    anchor_lang::solana_program::program::invoke_signed(
+        &ix,
+        &[payer.clone(), account_info.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+
+    vesting_account.try_serialize(&mut &mut account_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
 /// Helper function to process a single vesting account during batch initialization.
+#[cfg(feature = "legacy-batch")]
 pub fn process_single_vesting_account<'info>(
     index: usize,
     arg: &BatchVestingArgs,
@@ -756,12 +2098,7 @@ pub fn process_single_vesting_account<'info>(
     system_program_info: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<Pubkey> {
-    require!(arg.duration > 0, VestingError::InvalidDuration);
-    require!(
-        arg.cliff_period >= 0 && arg.cliff_period <= arg.duration,
-        VestingError::InvalidCliffPeriod
-    );
-    require!(arg.total_amount > 0, VestingError::InvalidAmount);
+    validate_schedule(arg.cliff_period, arg.duration, arg.total_amount)?;
 
     let account_info = remaining_accounts
         .get(index)
@@ -778,90 +2115,195 @@ pub fn process_single_vesting_account<'info>(
         VestingError::InvalidAccount
     );
 
-    require_eq!(
-        account_info.owner,
-        &System::id(),
-        VestingError::InvalidAccountOwner
-    );
-
-    let space = 8 + VestingAccount::LEN;
-    let rent = Rent::get()?.minimum_balance(space);
-
-    let ix = anchor_lang::solana_program::system_instruction::create_account(
-        admin_info.key,
-        account_info.key,
-        rent,
-        space as u64,
+    create_vesting_account(
+        account_info,
+        admin_info,
+        system_program_info,
         program_id,
-    );
-
-    let seeds = &[
-        b"vesting".as_ref(),
-        vault_key.as_ref(),
-        arg.mint.as_ref(),
-        arg.beneficiary.as_ref(),
-        &[bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
-
-    anchor_lang::solana_program::program::invoke_signed(
-        &ix,
         &[
-            admin_info.clone(),
-            account_info.clone(),
-            system_program_info.clone(),
+            b"vesting",
+            vault_key.as_ref(),
+            arg.mint.as_ref(),
+            arg.beneficiary.as_ref(),
+            &[bump],
         ],
-        signer_seeds,
+        &VestingAccount::new(
+            arg.beneficiary,
+            arg.mint,
+            arg.start_time,
+            arg.cliff_period,
+            arg.duration,
+            arg.total_amount,
+            bump,
+            false,
+        ),
     )?;
 
-    let vesting_data = VestingAccount {
-        beneficiary: arg.beneficiary,
-        mint: arg.mint,
-        start_time: arg.start_time,
-        cliff_period: arg.cliff_period,
-        duration: arg.duration,
-        total_amount: arg.total_amount,
-        claimed_amount: 0,
-        paused: false,
-        initialized: true,
-        revoked: false,
-        bump,
-    };
-
-    vesting_data.try_serialize(&mut &mut account_info.data.borrow_mut()[..])?;
-
     Ok(*account_info.key)
 }
 
-/// Emitted when tokens are claimed from a vesting account.
+/// Domain separator for Merkle leaves, so an inner node can never be passed off as a leaf.
+pub const MERKLE_LEAF_PREFIX: &[u8] = &[0];
+/// Domain separator for inner Merkle nodes.
+pub const MERKLE_NODE_PREFIX: &[u8] = &[1];
+
+/// Hashes a distribution leaf describing one beneficiary's grant.
+pub fn merkle_leaf(
+    beneficiary: &Pubkey,
+    amount: u64,
+    start_time: i64,
+    cliff_period: i64,
+    duration: i64,
+) -> [u8; 32] {
+    hashv(&[
+        MERKLE_LEAF_PREFIX,
+        beneficiary.as_ref(),
+        &amount.to_le_bytes(),
+        &start_time.to_le_bytes(),
+        &cliff_period.to_le_bytes(),
+        &duration.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hashes two sibling nodes. Pairs are sorted first, so proofs do not need to encode left/right positions.
+pub fn merkle_node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[MERKLE_NODE_PREFIX, &left, &right]).to_bytes()
+}
+
+/// Verifies that `leaf` is part of the tree with the given `root`.
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| merkle_node(node, *sibling)) == root
+}
+
+// Every event carries the `timestamp` and `slot` it was emitted at. Events about a vesting account carry the
+// account's resulting `claimed_amount` and `total_amount`, and events that move tokens the vault token account's
+// resulting `vault_balance`, so the event stream alone is enough to follow grant and vault state.
+
+/// Emitted when tokens are claimed from a vesting account.
+#[event]
+pub struct ClaimEvent {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PauseEvent {
+    pub vesting_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UnpauseEvent {
+    pub vesting_account: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+/// Emitted when a vesting account is revoked. `remaining_amount` is the unvested amount sent to the
+/// recovery destination; `beneficiary_amount` is the vested amount paid out on a good-leaver revoke.
 #[event]
-pub struct ClaimEvent {
+pub struct RevokeVestingEvent {
     pub vesting_account: Pubkey,
     pub beneficiary: Pubkey,
-    pub amount: u64,
+    pub admin: Pubkey,
+    pub remaining_amount: u64,
+    pub beneficiary_amount: u64,
     pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
-pub struct PauseEvent {
+pub struct TerminateVestingEvent {
     pub vesting_account: Pubkey,
     pub admin: Pubkey,
+    pub end_time: i64,
+    pub vested_amount: u64,
+    pub remaining_amount: u64,
     pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
-pub struct UnpauseEvent {
+pub struct ReduceGrantEvent {
     pub vesting_account: Pubkey,
     pub admin: Pubkey,
+    pub old_total: u64,
+    pub new_total: u64,
     pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
-pub struct RevokeVestingEvent {
+pub struct VestingAmendedEvent {
     pub vesting_account: Pubkey,
     pub admin: Pubkey,
-    pub remaining_amount: u64,
+    pub old_total: u64,
+    pub new_total: u64,
+    pub old_duration: i64,
+    pub new_duration: i64,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VestingSplitEvent {
+    pub vesting_account: Pubkey,
+    pub new_vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub new_claimed_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VestingMergedEvent {
+    pub vesting_account: Pubkey,
+    pub merged_account: Pubkey,
+    pub amount: u64,
     pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -871,6 +2313,11 @@ pub struct InstantUnlockEvent {
     pub admin: Pubkey,
     pub amount: u64,
     pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -879,6 +2326,9 @@ pub struct EmergencyRecoverEvent {
     pub admin: Pubkey,
     pub amount: u64,
     pub mint: Pubkey,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -887,6 +2337,9 @@ pub struct FundVaultExistingEvent {
     pub admin: Pubkey,
     pub amount: u64,
     pub mint: Pubkey,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -895,6 +2348,8 @@ pub struct UpdateAdminEvent {
     pub old_admin: Pubkey,
     pub new_admin: Pubkey,
     pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -902,6 +2357,8 @@ pub struct PauseVaultEvent {
     pub vault: Pubkey,
     pub admin: Pubkey,
     pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -909,6 +2366,8 @@ pub struct UnpauseVaultEvent {
     pub vault: Pubkey,
     pub admin: Pubkey,
     pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -918,6 +2377,65 @@ pub struct BatchInitializeVestingEvent {
     pub num_accounts: u64,
     pub vesting_account: Pubkey,
     pub mint: Pubkey,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DistributionInitializedEvent {
+    pub distribution: Pubkey,
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub root: [u8; 32],
+    pub total_amount: u64,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DistributionClosedEvent {
+    pub distribution: Pubkey,
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub released_amount: u64,
+    pub num_grants: u64,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerkleVestingCreatedEvent {
+    pub distribution: Pubkey,
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+/// Outcome of a single entry of `batch_initialize_vesting_idempotent`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchItemStatus {
+    Created,
+    Skipped,
+}
+
+#[event]
+pub struct BatchItemResultEvent {
+    pub vault: Pubkey,
+    pub index: u32,
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub status: BatchItemStatus,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -925,6 +2443,8 @@ pub struct VaultInitializedEvent {
     pub vault: Pubkey,
     pub admin: Pubkey,
     pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -933,6 +2453,9 @@ pub struct VestingInitializedEvent {
     pub beneficiary: Pubkey,
     pub mint: Pubkey,
     pub total_amount: u64,
+    pub irrevocable: bool,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
 #[event]
@@ -941,8 +2464,32 @@ pub struct VestingReinitializedEvent {
     pub beneficiary: Pubkey,
     pub mint: Pubkey,
     pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VaultMigratedEvent {
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VestingMigratedEvent {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+    pub slot: u64,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct InitializeVault<'info> {
     #[account(init, payer = payer, seeds = [b"vault", mint.key().as_ref()], bump, space = 8 + Vault::LEN)]
@@ -953,6 +2500,7 @@ pub struct InitializeVault<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct ReinitializeVesting<'info> {
     #[account(
@@ -963,6 +2511,7 @@ pub struct ReinitializeVesting<'info> {
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
+        mut,
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
     )]
@@ -975,6 +2524,7 @@ pub struct ReinitializeVesting<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct UpdateAdmin<'info> {
     #[account(
@@ -987,6 +2537,31 @@ pub struct UpdateAdmin<'info> {
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// CHECK: an earlier layout that `Account<Vault>` cannot load; the handler checks the owner and size, then
+    /// reads the admin once the account is resized.
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateVesting<'info> {
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct InitializeVesting<'info> {
     #[account(
@@ -997,7 +2572,7 @@ pub struct InitializeVesting<'info> {
         bump
     )]
     pub vesting_account: Account<'info, VestingAccount>,
-    #[account(seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]   
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
     pub mint: Account<'info, Mint>,
     pub beneficiary: SystemAccount<'info>,
@@ -1007,36 +2582,212 @@ pub struct InitializeVesting<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct Claim<'info> {
     #[account(mut,
         seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), beneficiary.key().as_ref()],
         bump = vesting_account.bump,
-        has_one = beneficiary,
+        has_one = beneficiary,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut,
+        constraint = vault_token_account.mint == mint.key(),
+        constraint = vault_token_account.owner == vault.key()
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,   
+    #[account(mut,
+        constraint = beneficiary_token_account.mint == mint.key()
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AdminClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
+        bump = vesting_account.bump,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        constraint = vault_token_account.mint == mint.key(),
+        constraint = vault_token_account.owner == vault.key()
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.mint == mint.key()
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetClaimable<'info> {
+    #[account(
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), beneficiary.key().as_ref()],
+        bump = vesting_account.bump,
+        has_one = beneficiary,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    pub beneficiary: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetVestingStatus<'info> {
+    #[account(has_one = mint)]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct GetVaultSummary<'info> {
+    #[account(
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        constraint = vault_token_account.mint == mint.key(),
+        constraint = vault_token_account.owner == vault.key()
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct PauseOrUnpause<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
+        bump = vesting_account.bump,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
+        bump = vesting_account.bump,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        constraint = vault_token_account.mint == mint.key(),
+        constraint = vault_token_account.owner == vault.key()
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = recovery_destination.mint == mint.key()
+    )]
+    pub recovery_destination: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeVestingGoodLeaver<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
+        bump = vesting_account.bump,
         has_one = mint
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
+        mut,
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
     )]
     pub vault: Account<'info, Vault>,
-    #[account(mut,
+    #[account(
+        mut,
         constraint = vault_token_account.mint == mint.key(),
         constraint = vault_token_account.owner == vault.key()
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,   
-    #[account(mut,
-        constraint = beneficiary_token_account.mint == mint.key()
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.mint == mint.key(),
+        constraint = beneficiary_token_account.owner == vesting_account.beneficiary
     )]
     pub beneficiary_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = recovery_destination.mint == mint.key()
+    )]
+    pub recovery_destination: Account<'info, TokenAccount>,
     pub mint: Account<'info, Mint>,
-    pub beneficiary: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct AdminClaim<'info> {
+pub struct AmendVesting<'info> {
     #[account(
         mut,
         seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
@@ -1045,6 +2796,7 @@ pub struct AdminClaim<'info> {
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
+        mut,
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
     )]
@@ -1057,17 +2809,21 @@ pub struct AdminClaim<'info> {
     pub vault_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = beneficiary_token_account.mint == mint.key()
+        constraint = source_token_account.mint == mint.key(),
+        constraint = source_token_account.owner == admin.key()
     )]
-    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    pub source_token_account: Account<'info, TokenAccount>,
     pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
     pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct GetClaimable<'info> {
+#[instruction(amount: u64, new_beneficiary: Pubkey)]
+pub struct SplitVesting<'info> {
     #[account(
+        mut,
         seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), beneficiary.key().as_ref()],
         bump = vesting_account.bump,
         has_one = beneficiary,
@@ -1075,16 +2831,26 @@ pub struct GetClaimable<'info> {
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
-        seeds = [b"vault", mint.key().as_ref()],
-        bump = vault.bump
+        init,
+        payer = payer,
+        space = 8 + VestingAccount::LEN,
+        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), new_beneficiary.as_ref()],
+        bump
     )]
+    pub new_vesting_account: Account<'info, VestingAccount>,
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
     pub mint: Account<'info, Mint>,
-    pub beneficiary: SystemAccount<'info>,
+    pub beneficiary: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct PauseOrUnpause<'info> {
+pub struct MergeVesting<'info> {
     #[account(
         mut,
         seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
@@ -1093,16 +2859,21 @@ pub struct PauseOrUnpause<'info> {
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
+        mut,
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
     )]
     pub vault: Account<'info, Vault>,
     pub mint: Account<'info, Mint>,
+    /// Receives the rent of the closed sources.
+    #[account(mut, address = vesting_account.beneficiary)]
+    pub beneficiary: SystemAccount<'info>,
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct RevokeVesting<'info> {
+pub struct InstantUnlock<'info> {
     #[account(
         mut,
         seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
@@ -1111,6 +2882,7 @@ pub struct RevokeVesting<'info> {
     )]
     pub vesting_account: Account<'info, VestingAccount>,
     #[account(
+        mut,
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
     )]
@@ -1123,23 +2895,17 @@ pub struct RevokeVesting<'info> {
     pub vault_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = recovery_destination.mint == mint.key()
+        constraint = beneficiary_token_account.mint == mint.key()
     )]
-    pub recovery_destination: Account<'info, TokenAccount>,
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
     pub mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct InstantUnlock<'info> {
-    #[account(
-        mut,
-        seeds = [b"vesting", vault.key().as_ref(), mint.key().as_ref(), vesting_account.beneficiary.key().as_ref()],
-        bump = vesting_account.bump,
-        has_one = mint
-    )]
-    pub vesting_account: Account<'info, VestingAccount>,
+pub struct EmergencyRecover<'info> {
     #[account(
         seeds = [b"vault", mint.key().as_ref()],
         bump = vault.bump
@@ -1153,20 +2919,38 @@ pub struct InstantUnlock<'info> {
     pub vault_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = beneficiary_token_account.mint == mint.key()
+        constraint = recovery_destination.mint == mint.key()
     )]
-    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    pub recovery_destination: Account<'info, TokenAccount>,
     pub mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct EmergencyRecover<'info> {
-    #[account(
-        seeds = [b"vault", mint.key().as_ref()],
-        bump = vault.bump
-    )]
+pub struct BatchInitializeVesting<'info> {
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct BatchAdminAction<'info> {
+    #[account(seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct BatchRevokeVesting<'info> {
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
     #[account(
         mut,
@@ -1184,16 +2968,99 @@ pub struct EmergencyRecover<'info> {
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct BatchInitializeVesting<'info> {
-    #[account(seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
+#[instruction(id: u64)]
+pub struct InitializeDistribution<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MerkleDistribution::LEN,
+        seeds = [b"distribution", vault.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseDistribution<'info> {
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"distribution", vault.key().as_ref(), distribution.id.to_le_bytes().as_ref()],
+        bump = distribution.bump,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
     pub mint: Account<'info, Mint>,
     #[account(mut)]
     pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimMerkle<'info> {
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        space = 8 + VestingAccount::LEN,
+        seeds = [b"vesting", distribution.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        space = 8 + MerkleClaimReceipt::LEN,
+        seeds = [b"merkle_receipt", distribution.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub claim_receipt: Account<'info, MerkleClaimReceipt>,
+    #[account(
+        mut,
+        seeds = [b"distribution", vault.key().as_ref(), distribution.id.to_le_bytes().as_ref()],
+        bump = distribution.bump,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        constraint = vault_token_account.mint == mint.key(),
+        constraint = vault_token_account.owner == vault.key()
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.mint == mint.key(),
+        constraint = beneficiary_token_account.owner == beneficiary.key()
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct PauseVault<'info> {
     #[account(
@@ -1206,6 +3073,7 @@ pub struct PauseVault<'info> {
     pub admin: Signer<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct FundVaultExisting<'info> {
     #[account(
@@ -1243,6 +3111,72 @@ pub struct BatchVestingArgs {
     pub total_amount: u64,
 }
 
+/// Why a grant has nothing claimable, or `Claimable` if it does.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VestingStatusReason {
+    Claimable,
+    NotInitialized,
+    Revoked,
+    Paused,
+    VaultPaused,
+    BeforeCliff,
+    FullyClaimed,
+    /// Everything vested so far has been claimed and more vests later.
+    AwaitingUnlock,
+}
+
+/// State of a vesting account at a point in time, returned by `get_vesting_status`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingStatus {
+    pub vested: u64,
+    pub claimed: u64,
+    /// What `claim` would transfer now; zero while the grant or vault is paused.
+    pub claimable: u64,
+    /// Not vested yet.
+    pub locked: u64,
+    /// Earliest time more tokens vest, or zero if nothing more will.
+    pub next_unlock_time: i64,
+    /// Time the grant is fully vested: before `start_time + duration` for a reduced grant, and no later than a
+    /// termination.
+    pub end_time: i64,
+    pub paused: bool,
+    pub vault_paused: bool,
+    pub revoked: bool,
+    pub reason: VestingStatusReason,
+}
+
+/// Aggregate state of a vault, returned by `get_vault_summary`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultSummary {
+    pub admin: Pubkey,
+    pub paused: bool,
+    /// Balance of the vault's token account.
+    pub vault_balance: u64,
+    pub total_committed: u64,
+    pub total_claimed: u64,
+    pub active_grants: u64,
+    pub distribution_reserved: u64,
+    /// Balance left after everything still owed to grants (`total_committed - total_claimed`) and reserved by
+    /// Merkle distributions (`distribution_reserved`); negative if the vault is underfunded.
+    pub surplus: i128,
+}
+
+/// Schedule shared by every entry of a compact batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchScheduleArgs {
+    pub start_time: i64,
+    pub cliff_period: i64,
+    pub duration: i64,
+}
+
+/// A single grant in a compact batch. `bump` must be the canonical bump of the beneficiary's vesting PDA.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchVestingEntry {
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub bump: u8,
+}
+
 impl VestingAccount {
     pub const LEN: usize = 32 + // beneficiary (Pubkey)
         32 + // Mint (Pubkey)
@@ -1254,14 +3188,305 @@ impl VestingAccount {
         1 +  // paused (bool)
         1 +  // initialized (bool)
         1 +  // revoked (bool)
-        1; // bump (u8)
+        1 +  // bump (u8)
+        8 +  // schedule_amount (u64)
+        8 +  // terminated_at (i64)
+        1; // irrevocable (bool)
+
+    /// Size of a grant written by earlier program versions, which ends after `bump`.
+    pub const V1_LEN: usize = Self::LEN - 8 - 8 - 1;
+
+    /// Decodes a grant in the layout of earlier program versions, checking its discriminator. Its schedule
+    /// follows `total_amount`, it was never terminated and it is revocable.
+    pub fn try_deserialize_v1(data: &[u8]) -> Result<Self> {
+        require!(data.len() != 8 + Self::LEN, VestingError::AlreadyMigrated);
+        require_eq!(data.len(), 8 + Self::V1_LEN, VestingError::InvalidAccount);
+        let mut padded = data.to_vec();
+        padded.resize(8 + Self::LEN, 0);
+        let mut vesting_account = Self::try_deserialize(&mut &padded[..])?;
+        vesting_account.schedule_amount = vesting_account.total_amount;
+        Ok(vesting_account)
+    }
+}
+
+impl VestingAccount {
+    /// Builds a freshly initialized grant with nothing claimed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        beneficiary: Pubkey,
+        mint: Pubkey,
+        start_time: i64,
+        cliff_period: i64,
+        duration: i64,
+        total_amount: u64,
+        bump: u8,
+        irrevocable: bool,
+    ) -> Self {
+        Self {
+            beneficiary,
+            mint,
+            start_time,
+            cliff_period,
+            duration,
+            total_amount,
+            claimed_amount: 0,
+            paused: false,
+            initialized: true,
+            revoked: false,
+            bump,
+            schedule_amount: total_amount,
+            terminated_at: 0,
+            irrevocable,
+        }
+    }
+
+    /// Returns the amount vested at `now` under the linear schedule, or zero before the cliff.
+    /// Vesting follows the rate of `schedule_amount` and never exceeds `total_amount`.
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.start_time);
+        if elapsed <= self.cliff_period {
+            return Ok(0);
+        }
+        if elapsed >= self.duration {
+            return Ok(self.total_amount);
+        }
+        let vested = (self.schedule_amount as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(VestingError::InvalidAmount)?
+            .checked_div(self.duration as u128)
+            .ok_or(VestingError::InvalidAmount)?;
+        Ok((vested as u64).min(self.total_amount))
+    }
+
+    /// Earliest time after `now` at which the vested amount grows, or zero if it never will.
+    pub fn next_unlock_time(&self, now: i64) -> Result<i64> {
+        let vested = self.vested_amount(now)?;
+        if self.revoked || vested >= self.total_amount {
+            return Ok(0);
+        }
+        // The smallest elapsed time with `schedule_amount * elapsed / duration > vested`, past the cliff.
+        let elapsed = if self.schedule_amount == 0 {
+            self.duration
+        } else {
+            ((vested as u128 + 1)
+                .checked_mul(self.duration as u128)
+                .ok_or(VestingError::InvalidAmount)?
+                .div_ceil(self.schedule_amount as u128) as i64)
+                .min(self.duration)
+        };
+        let elapsed = elapsed
+            .max(self.cliff_period + 1)
+            .max(now.saturating_sub(self.start_time) + 1);
+        Ok(self.start_time.saturating_add(elapsed))
+    }
+
+    /// Time at which `total_amount` has fully vested. A reduced grant keeps releasing at the rate of
+    /// `schedule_amount`, so it finishes before `start_time + duration`.
+    pub fn schedule_end(&self) -> Result<i64> {
+        // The smallest elapsed time with `schedule_amount * elapsed / duration >= total_amount`, past the cliff.
+        let elapsed = if self.schedule_amount == 0 {
+            self.duration
+        } else {
+            ((self.total_amount as u128)
+                .checked_mul(self.duration as u128)
+                .ok_or(VestingError::InvalidAmount)?
+                .div_ceil(self.schedule_amount as u128) as i64)
+                .min(self.duration)
+        };
+        Ok(self.start_time.saturating_add(elapsed.max(self.cliff_period + 1)))
+    }
+
+    /// Time from which nothing more vests: the schedule end, or the termination date if that comes first.
+    pub fn end_time(&self) -> Result<i64> {
+        let schedule_end = self.schedule_end()?;
+        Ok(if self.terminated_at != 0 {
+            self.terminated_at.min(schedule_end)
+        } else {
+            schedule_end
+        })
+    }
+
+    /// Whether a vault pause stops the beneficiary from claiming. Irrevocable grants cannot be paused one by
+    /// one, so pausing the vault does not freeze them either.
+    pub fn blocked_by_vault_pause(&self, vault_paused: bool) -> bool {
+        vault_paused && !self.irrevocable
+    }
+
+    /// Status of the grant at `now`, given whether its vault is paused.
+    pub fn status(&self, now: i64, vault_paused: bool) -> Result<VestingStatus> {
+        let vested = self.vested_amount(now)?;
+        let unclaimed = vested.saturating_sub(self.claimed_amount);
+        let elapsed = now.saturating_sub(self.start_time);
+        let reason = if !self.initialized {
+            VestingStatusReason::NotInitialized
+        } else if self.revoked {
+            VestingStatusReason::Revoked
+        } else if self.paused {
+            VestingStatusReason::Paused
+        } else if self.blocked_by_vault_pause(vault_paused) {
+            VestingStatusReason::VaultPaused
+        } else if unclaimed > 0 {
+            VestingStatusReason::Claimable
+        } else if elapsed <= self.cliff_period {
+            VestingStatusReason::BeforeCliff
+        } else if self.claimed_amount >= self.total_amount {
+            VestingStatusReason::FullyClaimed
+        } else {
+            VestingStatusReason::AwaitingUnlock
+        };
+        Ok(VestingStatus {
+            vested,
+            claimed: self.claimed_amount,
+            claimable: if reason == VestingStatusReason::Claimable { unclaimed } else { 0 },
+            locked: self.total_amount.saturating_sub(vested),
+            next_unlock_time: self.next_unlock_time(now)?,
+            end_time: self.end_time()?,
+            paused: self.paused,
+            vault_paused,
+            revoked: self.revoked,
+            reason,
+        })
+    }
+
+    /// Whether the grant still has tokens to pay out: initialized, not revoked and not fully claimed.
+    pub fn is_active(&self) -> bool {
+        self.initialized && !self.revoked && self.claimed_amount < self.total_amount
+    }
+
+    /// Whether the grant was created with exactly the parameters in `arg`. Batch entries always create
+    /// revocable grants, so an irrevocable grant never matches.
+    pub fn matches(&self, arg: &BatchVestingArgs) -> bool {
+        !self.irrevocable
+            && self.beneficiary == arg.beneficiary
+            && self.mint == arg.mint
+            && self.start_time == arg.start_time
+            && self.cliff_period == arg.cliff_period
+            && self.duration == arg.duration
+            && self.total_amount == arg.total_amount
+    }
+
+    /// Whether the grant can take part in a merge: live, unpaused, never terminated and never reduced,
+    /// so that summing totals keeps the combined release curve identical.
+    pub fn is_mergeable(&self) -> bool {
+        self.initialized
+            && !self.revoked
+            && !self.paused
+            && self.terminated_at == 0
+            && self.schedule_amount == self.total_amount
+    }
+}
+
+impl MerkleDistribution {
+    pub const LEN: usize = 1 +  // bump (u8)
+        32 + // vault (Pubkey)
+        32 + // mint (Pubkey)
+        8 +  // id (u64)
+        32 + // root ([u8; 32])
+        8 +  // total_amount (u64)
+        8 +  // allocated_amount (u64)
+        8; // num_grants (u64)
+}
+
+impl MerkleClaimReceipt {
+    pub const LEN: usize = 1 +  // bump (u8)
+        1 +  // claimed (bool)
+        32; // leaf ([u8; 32])
 }
 
 impl Vault {
     pub const LEN: usize = 1 +  // bump (u8)
         32 + // admin (Pubkey)
         1 +  // paused (bool)
-        1; // initialized (bool)
+        1 +  // initialized (bool)
+        8 +  // irrevocable_outstanding (u64)
+        8 +  // total_committed (u64)
+        8 +  // total_claimed (u64)
+        8 +  // active_grants (u64)
+        8; // distribution_reserved (u64)
+
+    /// Size of a vault written by earlier program versions, which ends after `initialized`.
+    pub const V1_LEN: usize = 1 + 32 + 1 + 1;
+
+    /// Records a new grant of `amount`.
+    pub fn record_grant(&mut self, amount: u64) -> Result<()> {
+        self.total_committed = self
+            .total_committed
+            .checked_add(amount)
+            .ok_or(VestingError::InvalidAmount)?;
+        self.active_grants += 1;
+        Ok(())
+    }
+
+    /// Records `amount` added to an existing grant.
+    pub fn record_top_up(&mut self, amount: u64) -> Result<()> {
+        self.total_committed = self
+            .total_committed
+            .checked_add(amount)
+            .ok_or(VestingError::InvalidAmount)?;
+        Ok(())
+    }
+
+    /// Records `amount` of a grant returned to the admin by a revocation, termination or reduction.
+    pub fn record_release(&mut self, amount: u64) {
+        self.total_committed = self.total_committed.saturating_sub(amount);
+    }
+
+    /// Records `amount` paid out to a beneficiary.
+    pub fn record_payout(&mut self, amount: u64) -> Result<()> {
+        self.total_claimed = self
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(VestingError::InvalidAmount)?;
+        Ok(())
+    }
+
+    /// Records `amount` reserved by a new Merkle distribution.
+    pub fn record_reservation(&mut self, amount: u64) -> Result<()> {
+        self.distribution_reserved = self
+            .distribution_reserved
+            .checked_add(amount)
+            .ok_or(VestingError::InvalidAmount)?;
+        Ok(())
+    }
+
+    /// Records `amount` of a distribution's reservation turned into a grant by `claim_merkle`.
+    pub fn record_allocation(&mut self, amount: u64) {
+        self.distribution_reserved = self.distribution_reserved.saturating_sub(amount);
+    }
+
+    /// Records `amount` of a distribution's reservation released unallocated by `close_distribution`.
+    pub fn release_reservation(&mut self, amount: u64) {
+        self.distribution_reserved = self.distribution_reserved.saturating_sub(amount);
+    }
+
+    /// Records a grant brought over from an earlier program version by `migrate_vesting`, which the totals
+    /// have never counted.
+    pub fn record_migrated_grant(&mut self, vesting_account: &VestingAccount) -> Result<()> {
+        self.total_committed = self
+            .total_committed
+            .checked_add(vesting_account.total_amount)
+            .ok_or(VestingError::InvalidAmount)?;
+        self.record_payout(vesting_account.claimed_amount)?;
+        self.record_activity(false, vesting_account.is_active());
+        Ok(())
+    }
+
+    /// Keeps `active_grants` in step with a grant that was `was_active` before an update and `is_active` after.
+    pub fn record_activity(&mut self, was_active: bool, is_active: bool) {
+        match (was_active, is_active) {
+            (false, true) => self.active_grants += 1,
+            (true, false) => self.active_grants = self.active_grants.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// What `vault_balance` leaves after everything still owed to grants and reserved by Merkle distributions;
+    /// negative if the vault is underfunded.
+    pub fn surplus(&self, vault_balance: u64) -> i128 {
+        vault_balance as i128
+            - (self.total_committed as i128 - self.total_claimed as i128)
+            - self.distribution_reserved as i128
+    }
 }
 
 #[account]
@@ -1270,6 +3495,40 @@ pub struct Vault {
     pub admin: Pubkey,
     pub paused: bool,
     pub initialized: bool,
+    /// Tokens still owed to irrevocable grants, which `emergency_recover` leaves in the vault.
+    pub irrevocable_outstanding: u64,
+    /// Tokens granted across all grants, less what revocations, terminations and reductions returned.
+    pub total_committed: u64,
+    /// Tokens paid out to beneficiaries.
+    pub total_claimed: u64,
+    /// Grants that are neither revoked nor fully paid out.
+    pub active_grants: u64,
+    /// Tokens Merkle distributions can still turn into grants: each one's `total_amount - allocated_amount`.
+    pub distribution_reserved: u64,
+}
+
+/// A Merkle root of grants that beneficiaries turn into vesting accounts on their first `claim_merkle`.
+#[account]
+pub struct MerkleDistribution {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub id: u64,
+    pub root: [u8; 32],
+    /// Upper bound on the sum of all grants created from this distribution.
+    pub total_amount: u64,
+    pub allocated_amount: u64,
+    pub num_grants: u64,
+}
+
+/// Marks a distribution's leaf for a beneficiary as claimed. Receipts are never closed, so a leaf cannot
+/// create a second grant after its vesting account is merged away.
+#[account]
+pub struct MerkleClaimReceipt {
+    pub bump: u8,
+    pub claimed: bool,
+    /// Hash of the leaf the grant was created from.
+    pub leaf: [u8; 32],
 }
 
 #[account]
@@ -1285,6 +3544,12 @@ pub struct VestingAccount {
     pub initialized: bool,
     pub revoked: bool,
     pub bump: u8,
+    /// Amount the linear release rate is computed from; `total_amount` caps what actually vests.
+    pub schedule_amount: u64,
+    /// Unix timestamp the schedule was terminated at, or zero if it was never terminated.
+    pub terminated_at: i64,
+    /// Set at initialization; the grant can then never be revoked, terminated, paused or redirected.
+    pub irrevocable: bool,
 }
 
 #[error_code]
@@ -1327,4 +3592,30 @@ pub enum VestingError {
     NotRevoked,
     #[msg("Invalid number of accounts provided.")]
     InvalidAccountCount,
+    #[msg("Vesting account has already been terminated.")]
+    AlreadyTerminated,
+    #[msg("Vesting account is irrevocable.")]
+    Irrevocable,
+    #[msg("New total must be below the current total and not below the vested amount.")]
+    InvalidGrantReduction,
+    #[msg("Amendment must increase the total amount or extend the duration, and never decrease either.")]
+    InvalidAmendment,
+    #[msg("Amendment would reduce the amount already vested.")]
+    AmendmentReducesVested,
+    #[msg("Vesting accounts are not compatible for merging.")]
+    IncompatibleVesting,
+    #[msg("Invalid Merkle proof.")]
+    InvalidProof,
+    #[msg("Merkle distribution total exceeded.")]
+    DistributionExhausted,
+    #[msg("Vesting account already exists with different parameters.")]
+    ConflictingVestingAccount,
+    #[msg("Termination end time must be positive and not before the start time.")]
+    InvalidEndTime,
+    #[msg("This Merkle leaf has already been claimed.")]
+    LeafAlreadyClaimed,
+    #[msg("The PDA bump is not the canonical bump.")]
+    NonCanonicalBump,
+    #[msg("Account already has the current layout.")]
+    AlreadyMigrated,
 }
\ No newline at end of file
@@ -10,8 +10,20 @@ import {
   getAssociatedTokenAddress
 } from "@solana/spl-token";
 import * as assert from "assert";
-import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
-import { getVaultPda, getVestingPda } from "../frontend/derive"; // Import derive functions
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
+import {
+  getVaultPda,
+  getVestingPda,
+  getDistributionPda,
+  getMerkleVestingPda,
+  getMerkleClaimReceiptPda,
+} from "../frontend/derive"; // Import derive functions
+import { createHash } from "crypto";
 import { Init } from "v8";
 
 describe("shorthusk-vesting (tests)", () => {
@@ -20,6 +32,21 @@ describe("shorthusk-vesting (tests)", () => {
 
   const program = anchor.workspace.ShorthuskVesting as Program<ShorthuskVesting>;
 
+  // Events a confirmed transaction logged, by name
+  const loggedEvents = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx.meta.logMessages));
+  };
+  const loggedEvent = async (signature: string, name: string) => {
+    const event = (await loggedEvents(signature)).find((e) => e.name === name);
+    assert.ok(event, `Expected a ${name} in the transaction logs`);
+    return event.data as any;
+  };
+
   let mint: PublicKey;
   let admin = provider.wallet;
   let newAdmin: Keypair = Keypair.generate();
@@ -251,7 +278,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(now),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(250_000_000)
+          new anchor.BN(250_000_000),
+          false
         )
         .accounts({
           vestingAccount: vestingPda1,
@@ -269,7 +297,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(now),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(250_000_000)
+          new anchor.BN(250_000_000),
+          false
         )
         .accounts({
           vestingAccount: vestingPda2,
@@ -376,7 +405,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(futureTime),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(250_000_000)
+          new anchor.BN(250_000_000),
+          false
         )
         .accounts({
           vestingAccount: futureVestingPda,
@@ -726,7 +756,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(250_000_000)
+        new anchor.BN(250_000_000),
+        false
       )
       .accounts({
         vestingAccount: newVestingPda,
@@ -819,7 +850,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(250_000_000)
+        new anchor.BN(250_000_000),
+        false
       )
       .accounts({
         vestingAccount: newVestingPda,
@@ -949,7 +981,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(now),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(300_000_000)
+          new anchor.BN(300_000_000),
+          false
         )
         .accounts({
           vestingAccount: newVestingPda,
@@ -1137,7 +1170,8 @@ describe("shorthusk-vesting (tests)", () => {
             new anchor.BN(now),
             new anchor.BN(30),
             new anchor.BN(300),
-            new anchor.BN(100_000_000)
+            new anchor.BN(100_000_000),
+            false
           )
           .accounts({
             vestingAccount: newVestingPda,
@@ -1183,7 +1217,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(100_000_000)
+        new anchor.BN(100_000_000),
+        false
       )
       .accounts({
         vestingAccount: newVestingPda,
@@ -1235,7 +1270,7 @@ describe("shorthusk-vesting (tests)", () => {
 
     try {
       const tx = await program.methods
-        .batchInitializeVesting(args)
+        .batchInitializeVestingV2(args)
         .accounts({
           vault: vaultPda,
           mint,
@@ -1243,11 +1278,14 @@ describe("shorthusk-vesting (tests)", () => {
           systemProgram: SystemProgram.programId,
         })
         .remainingAccounts(
-          batchVestingPdas.map((pda) => ({
-            pubkey: pda,
-            isWritable: true,
-            isSigner: false,
-          }))
+          batchVestingPdas.flatMap((pda, i) => [
+            { pubkey: pda, isWritable: true, isSigner: false },
+            {
+              pubkey: batchBeneficiaries[i].publicKey,
+              isWritable: false,
+              isSigner: false,
+            },
+          ])
         )
         .signers([newAdmin])
         .rpc({ commitment: "confirmed" });
@@ -1475,7 +1513,7 @@ describe("shorthusk-vesting (tests)", () => {
   
     try {
       await program.methods
-        .batchInitializeVesting(args)
+        .batchInitializeVestingV2(args)
         .accounts({
           vault: vaultPda,
           vaultToken: await getAssociatedTokenAddress(mint, vaultPda, true),
@@ -1564,6 +1602,10 @@ describe("shorthusk-vesting (tests)", () => {
         event.amount.toNumber() > 0,
         "Claimed amount should be greater than 0"
       );
+      assert.ok(event.claimedAmount.gte(event.amount));
+      assert.ok(event.claimedAmount.lte(event.totalAmount));
+      assert.ok(event.timestamp.toNumber() > 0);
+      assert.ok(event.slot.toNumber() <= slot);
     });
 
     try {
@@ -1596,7 +1638,8 @@ describe("shorthusk-vesting (tests)", () => {
             new anchor.BN(now),
             new anchor.BN(30),
             new anchor.BN(300),
-            new anchor.BN(250_000_000)
+            new anchor.BN(250_000_000),
+            false
           )
           .accounts({
             vestingAccount: newVestingPda,
@@ -1633,18 +1676,17 @@ describe("shorthusk-vesting (tests)", () => {
       totalAmount: new anchor.BN(100_000_000),
     }));
   
-    const remainingAccounts = vestingPdas.map((pda) => ({
-      pubkey: pda,
-      isWritable: true,
-      isSigner: false,
-    }));
+    const remainingAccounts = vestingPdas.flatMap((pda, i) => [
+      { pubkey: pda, isWritable: true, isSigner: false },
+      { pubkey: beneficiaries[i].publicKey, isWritable: false, isSigner: false },
+    ]);
   
     console.log("Remaining accounts count:", remainingAccounts.length);
     console.log("Vesting PDAs:", vestingPdas.map(pda => pda.toBase58()));
   
     try {
       const tx = await program.methods
-        .batchInitializeVesting(args)
+        .batchInitializeVestingV2(args)
         .accounts({
           vault: vaultPda,
           mint,
@@ -2345,7 +2387,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(123_000_000)
+        new anchor.BN(123_000_000),
+        false
       )
       .accounts({
         vestingAccount: vestingPda,
@@ -2466,7 +2509,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(123_000_000)
+        new anchor.BN(123_000_000),
+        false
       )
       .accounts({
         vestingAccount: vestingPda,
@@ -2578,7 +2622,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(now),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(100_000_000)
+          new anchor.BN(100_000_000),
+          false
         )
         .accounts({
           vestingAccount: vestingPda,
@@ -2636,7 +2681,8 @@ describe("shorthusk-vesting (tests)", () => {
           new anchor.BN(now),
           new anchor.BN(30),
           new anchor.BN(300),
-          new anchor.BN(100_000_000)
+          new anchor.BN(100_000_000),
+          false
         )
         .accounts({
           vestingAccount: vestingPda,
@@ -2815,7 +2861,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(100_000_000)
+        new anchor.BN(100_000_000),
+        false
       )
       .accounts({
         vestingAccount: vestingPda1,
@@ -2833,7 +2880,8 @@ describe("shorthusk-vesting (tests)", () => {
         new anchor.BN(now),
         new anchor.BN(30),
         new anchor.BN(300),
-        new anchor.BN(100_000_000)
+        new anchor.BN(100_000_000),
+        false
       )
       .accounts({
         vestingAccount: vestingPda2,
@@ -3141,4 +3189,2033 @@ describe("shorthusk-vesting (tests)", () => {
       await program.removeEventListener(listener);
     }
   });
+
+  it("Revokes a vesting account as a good leaver", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    // Start half-way through a 300s schedule so roughly half is already vested
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now - 150),
+        new anchor.BN(30),
+        new anchor.BN(300),
+        new anchor.BN(200_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      200_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(200_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const beneficiaryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      beneficiary.publicKey
+    );
+    const recoveryBefore = await getAccount(provider.connection, recoveryDestination);
+
+    // Grants are usually paused while a departure is handled; the good-leaver revoke must still go through.
+    await program.methods
+      .pause()
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        admin: newAdmin.publicKey,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    let emitted = null;
+    const listener = program.addEventListener("revokeVestingEvent", (event) => {
+      if (event.vestingAccount.toBase58() === vestingPda.toBase58()) {
+        emitted = event;
+      }
+    });
+
+    try {
+      await program.methods
+        .revokeVestingGoodLeaver()
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          beneficiaryTokenAccount: beneficiaryTokenAccount.address,
+          recoveryDestination,
+          mint,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc({ commitment: "confirmed" });
+
+      const revoked = await program.account.vestingAccount.fetch(vestingPda);
+      const beneficiaryAccount = await getAccount(
+        provider.connection,
+        beneficiaryTokenAccount.address
+      );
+      const recoveryAfter = await getAccount(provider.connection, recoveryDestination);
+      const paid = Number(beneficiaryAccount.amount);
+      const recovered = Number(recoveryAfter.amount - recoveryBefore.amount);
+
+      assert.strictEqual(revoked.revoked, true);
+      assert.strictEqual(revoked.claimedAmount.toNumber(), paid);
+      assert.strictEqual(revoked.totalAmount.toNumber(), paid);
+      assert.ok(paid >= 100_000_000, "Vested tokens should be paid to the beneficiary");
+      assert.strictEqual(paid + recovered, 200_000_000);
+
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      assert.ok(emitted, "RevokeVestingEvent should be emitted");
+      assert.strictEqual(emitted.beneficiaryAmount.toNumber(), paid);
+      assert.strictEqual(emitted.remainingAmount.toNumber(), recovered);
+      assert.strictEqual(emitted.beneficiary.toBase58(), revoked.beneficiary.toBase58());
+    } finally {
+      await program.removeEventListener(listener);
+    }
+  });
+
+  it("Terminates a vesting account at a past effective date", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+    const startTime = now - 150;
+    const endTime = startTime + 90;
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(startTime),
+        new anchor.BN(30),
+        new anchor.BN(300),
+        new anchor.BN(300_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      300_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(300_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const recoveryBefore = await getAccount(provider.connection, recoveryDestination);
+
+    // An end time of zero would read as "not terminated", and one before the start vests nothing
+    for (const invalidEndTime of [0, startTime - 1]) {
+      try {
+        await program.methods
+          .terminateVesting(new anchor.BN(invalidEndTime))
+          .accounts({
+            vestingAccount: vestingPda,
+            vault: vaultPda,
+            vaultTokenAccount,
+            mint,
+            recoveryDestination,
+            admin: newAdmin.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([newAdmin])
+          .rpc();
+        assert.fail("Should have failed with InvalidEndTime");
+      } catch (err) {
+        assert.match(err.toString(), /InvalidEndTime/);
+      }
+    }
+
+    const terminateTx = await program.methods
+      .terminateVesting(new anchor.BN(endTime))
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        vaultTokenAccount,
+        mint,
+        recoveryDestination,
+        admin: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc({ commitment: "confirmed" });
+
+    const terminated = await program.account.vestingAccount.fetch(vestingPda);
+    const recoveryAfter = await getAccount(provider.connection, recoveryDestination);
+    assert.strictEqual(terminated.terminatedAt.toNumber(), endTime);
+    assert.strictEqual(terminated.totalAmount.toNumber(), 90_000_000);
+    assert.strictEqual(terminated.revoked, false);
+    assert.strictEqual(
+      Number(recoveryAfter.amount - recoveryBefore.amount),
+      210_000_000
+    );
+
+    // The event carries the grant and vault state the termination left behind
+    const terminateEvent = await loggedEvent(terminateTx, "terminateVestingEvent");
+    const vaultAfterTerminate = await getAccount(provider.connection, vaultTokenAccount);
+    assert.strictEqual(terminateEvent.endTime.toNumber(), endTime);
+    assert.strictEqual(terminateEvent.remainingAmount.toNumber(), 210_000_000);
+    assert.strictEqual(terminateEvent.totalAmount.toNumber(), terminated.totalAmount.toNumber());
+    assert.strictEqual(terminateEvent.claimedAmount.toNumber(), terminated.claimedAmount.toNumber());
+    assert.strictEqual(terminateEvent.vaultBalance.toNumber(), Number(vaultAfterTerminate.amount));
+    assert.ok(terminateEvent.timestamp.toNumber() > 0);
+    assert.ok(terminateEvent.slot.toNumber() > 0);
+
+    // The beneficiary can still claim what vested up to the termination date
+    const beneficiaryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      beneficiary.publicKey
+    );
+    await program.methods
+      .claim()
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        vaultTokenAccount,
+        mint,
+        beneficiaryTokenAccount: beneficiaryTokenAccount.address,
+        beneficiary: beneficiary.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+    const beneficiaryAccount = await getAccount(
+      provider.connection,
+      beneficiaryTokenAccount.address
+    );
+    assert.strictEqual(Number(beneficiaryAccount.amount), 90_000_000);
+
+    try {
+      await program.methods
+        .terminateVesting(new anchor.BN(now))
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          mint,
+          recoveryDestination,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with AlreadyTerminated");
+    } catch (err) {
+      assert.match(err.toString(), /AlreadyTerminated/);
+    }
+  });
+
+  it("Lets irrevocable grants claim while the vault is paused", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now - 150),
+        new anchor.BN(30),
+        new anchor.BN(300),
+        new anchor.BN(100_000_000),
+        true
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      100_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(100_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const beneficiaryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      beneficiary.publicKey
+    );
+    await program.methods
+      .pauseVault()
+      .accounts({ vault: vaultPda, mint, admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+
+    try {
+      await program.methods
+        .claim()
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          mint,
+          beneficiaryTokenAccount: beneficiaryTokenAccount.address,
+          beneficiary: beneficiary.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc();
+
+      const beneficiaryAccount = await getAccount(
+        provider.connection,
+        beneficiaryTokenAccount.address
+      );
+      assert.ok(
+        Number(beneficiaryAccount.amount) >= 50_000_000,
+        "Vested tokens should be claimable despite the vault pause"
+      );
+    } finally {
+      await program.methods
+        .unpauseVault()
+        .accounts({ vault: vaultPda, mint, admin: newAdmin.publicKey })
+        .signers([newAdmin])
+        .rpc();
+    }
+  });
+
+  it("Protects irrevocable grants from revoke, pause and emergency recovery", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+    const vaultBefore = await program.account.vault.fetch(vaultPda);
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now),
+        new anchor.BN(30),
+        new anchor.BN(300),
+        new anchor.BN(150_000_000),
+        true
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const vesting = await program.account.vestingAccount.fetch(vestingPda);
+    assert.strictEqual(vesting.irrevocable, true);
+    const vaultAfterInit = await program.account.vault.fetch(vaultPda);
+    assert.strictEqual(
+      vaultAfterInit.irrevocableOutstanding.toNumber(),
+      vaultBefore.irrevocableOutstanding.toNumber() + 150_000_000
+    );
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      150_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(150_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    try {
+      await program.methods
+        .revokeVesting()
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          mint,
+          recoveryDestination,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with Irrevocable");
+    } catch (err) {
+      assert.match(err.toString(), /Irrevocable/);
+    }
+
+    try {
+      await program.methods
+        .pause()
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          mint,
+          admin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with Irrevocable");
+    } catch (err) {
+      assert.match(err.toString(), /Irrevocable/);
+    }
+
+    await program.methods
+      .emergencyRecover()
+      .accounts({
+        vault: vaultPda,
+        vaultTokenAccount,
+        recoveryDestination,
+        mint,
+        admin: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const vaultState = await program.account.vault.fetch(vaultPda);
+    const vaultBalance = await getAccount(provider.connection, vaultTokenAccount);
+    assert.strictEqual(
+      Number(vaultBalance.amount),
+      vaultState.irrevocableOutstanding.toNumber(),
+      "Emergency recovery should leave the irrevocable share in the vault"
+    );
+  });
+
+  it("Reduces a grant without going below the vested amount", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    // A quarter of the 400s schedule has already elapsed
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now - 100),
+        new anchor.BN(30),
+        new anchor.BN(400),
+        new anchor.BN(400_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      400_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(400_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    try {
+      await program.methods
+        .reduceGrant(new anchor.BN(50_000_000))
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          mint,
+          recoveryDestination,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with InvalidGrantReduction");
+    } catch (err) {
+      assert.match(err.toString(), /InvalidGrantReduction/);
+    }
+
+    const recoveryBefore = await getAccount(provider.connection, recoveryDestination);
+    const reduceTx = await program.methods
+      .reduceGrant(new anchor.BN(250_000_000))
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        vaultTokenAccount,
+        mint,
+        recoveryDestination,
+        admin: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc({ commitment: "confirmed" });
+
+    const reduced = await program.account.vestingAccount.fetch(vestingPda);
+    const recoveryAfter = await getAccount(provider.connection, recoveryDestination);
+    assert.strictEqual(reduced.totalAmount.toNumber(), 250_000_000);
+    assert.strictEqual(reduced.scheduleAmount.toNumber(), 400_000_000);
+    assert.strictEqual(reduced.duration.toNumber(), 400);
+    assert.strictEqual(
+      Number(recoveryAfter.amount - recoveryBefore.amount),
+      150_000_000
+    );
+
+    const reduceEvent = await loggedEvent(reduceTx, "reduceGrantEvent");
+    const vaultAfterReduce = await getAccount(provider.connection, vaultTokenAccount);
+    assert.strictEqual(reduceEvent.oldTotal.toNumber(), 400_000_000);
+    assert.strictEqual(reduceEvent.newTotal.toNumber(), 250_000_000);
+    assert.strictEqual(reduceEvent.totalAmount.toNumber(), reduced.totalAmount.toNumber());
+    assert.strictEqual(reduceEvent.claimedAmount.toNumber(), reduced.claimedAmount.toNumber());
+    assert.strictEqual(reduceEvent.vaultBalance.toNumber(), Number(vaultAfterReduce.amount));
+    assert.ok(reduceEvent.timestamp.toNumber() > 0);
+    assert.ok(reduceEvent.slot.toNumber() > 0);
+  });
+
+  it("Amends a live grant with a top-up and a longer duration", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now - 100),
+        new anchor.BN(30),
+        new anchor.BN(200),
+        new anchor.BN(100_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    // The top-up is paid from the admin's token account
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      100_000_000
+    );
+    const vaultBalanceBefore = await getAccount(provider.connection, vaultTokenAccount);
+
+    // Doubling the duration alone would halve the vested amount
+    try {
+      await program.methods
+        .amendVesting(new anchor.BN(100_000_000), new anchor.BN(400))
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          sourceTokenAccount: sourceTokenAccount.address,
+          mint,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with AmendmentReducesVested");
+    } catch (err) {
+      assert.match(err.toString(), /AmendmentReducesVested/);
+    }
+
+    const listener = program.addEventListener("vestingAmendedEvent", (event) => {
+      if (event.vestingAccount.toBase58() === vestingPda.toBase58()) {
+        assert.strictEqual(event.oldTotal.toNumber(), 100_000_000);
+        assert.strictEqual(event.newTotal.toNumber(), 200_000_000);
+        assert.strictEqual(event.oldDuration.toNumber(), 200);
+        assert.strictEqual(event.newDuration.toNumber(), 400);
+      }
+    });
+
+    try {
+      await program.methods
+        .amendVesting(new anchor.BN(200_000_000), new anchor.BN(400))
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          sourceTokenAccount: sourceTokenAccount.address,
+          mint,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([newAdmin])
+        .rpc();
+
+      const amended = await program.account.vestingAccount.fetch(vestingPda);
+      const vaultBalanceAfter = await getAccount(provider.connection, vaultTokenAccount);
+      assert.strictEqual(
+        Number(vaultBalanceAfter.amount - vaultBalanceBefore.amount),
+        100_000_000,
+        "The top-up should be transferred into the vault"
+      );
+      assert.strictEqual(amended.totalAmount.toNumber(), 200_000_000);
+      assert.strictEqual(amended.scheduleAmount.toNumber(), 200_000_000);
+      assert.strictEqual(amended.duration.toNumber(), 400);
+      assert.strictEqual(amended.claimedAmount.toNumber(), 0);
+    } finally {
+      await program.removeEventListener(listener);
+    }
+  });
+
+  it("Splits a vesting grant into a new grant for another beneficiary", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const coFounder = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+    const [newVestingPda] = getVestingPda(vaultPda, mint, coFounder.publicKey);
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now),
+        new anchor.BN(30),
+        new anchor.BN(300),
+        new anchor.BN(300_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    await program.methods
+      .splitVesting(new anchor.BN(100_000_000), coFounder.publicKey)
+      .accounts({
+        vestingAccount: vestingPda,
+        newVestingAccount: newVestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin, beneficiary])
+      .rpc();
+
+    const source = await program.account.vestingAccount.fetch(vestingPda);
+    const split = await program.account.vestingAccount.fetch(newVestingPda);
+    assert.strictEqual(source.totalAmount.toNumber(), 200_000_000);
+    assert.strictEqual(source.scheduleAmount.toNumber(), 200_000_000);
+    assert.strictEqual(split.totalAmount.toNumber(), 100_000_000);
+    assert.strictEqual(split.scheduleAmount.toNumber(), 100_000_000);
+    assert.strictEqual(split.beneficiary.toBase58(), coFounder.publicKey.toBase58());
+    assert.strictEqual(split.startTime.toNumber(), source.startTime.toNumber());
+    assert.strictEqual(split.cliffPeriod.toNumber(), 30);
+    assert.strictEqual(split.duration.toNumber(), 300);
+  });
+
+  it("Refuses to merge grants that are not compatible", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiaryA = Keypair.generate();
+    const beneficiaryB = Keypair.generate();
+    const [vestingPdaA] = getVestingPda(vaultPda, mint, beneficiaryA.publicKey);
+    const [vestingPdaB] = getVestingPda(vaultPda, mint, beneficiaryB.publicKey);
+
+    for (const [beneficiary, vestingPda] of [
+      [beneficiaryA, vestingPdaA],
+      [beneficiaryB, vestingPdaB],
+    ] as [Keypair, PublicKey][]) {
+      await program.methods
+        .initializeVesting(
+          new anchor.BN(now),
+          new anchor.BN(30),
+          new anchor.BN(300),
+          new anchor.BN(50_000_000),
+          false
+        )
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: vaultPda,
+          mint,
+          beneficiary: beneficiary.publicKey,
+          payer: newAdmin.publicKey,
+          admin: newAdmin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([newAdmin])
+        .rpc();
+    }
+
+    try {
+      await program.methods
+        .mergeVesting()
+        .accounts({
+          vestingAccount: vestingPdaA,
+          vault: vaultPda,
+          mint,
+          beneficiary: beneficiaryA.publicKey,
+          admin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with NotEnoughAccounts");
+    } catch (err) {
+      assert.match(err.toString(), /NotEnoughAccounts/);
+    }
+
+    // Grants belonging to different beneficiaries can never be merged
+    try {
+      await program.methods
+        .mergeVesting()
+        .accounts({
+          vestingAccount: vestingPdaA,
+          vault: vaultPda,
+          mint,
+          beneficiary: beneficiaryA.publicKey,
+          admin: newAdmin.publicKey,
+        })
+        .remainingAccounts([
+          { pubkey: vestingPdaB, isWritable: true, isSigner: false },
+        ])
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with IncompatibleVesting");
+    } catch (err) {
+      assert.match(err.toString(), /IncompatibleVesting/);
+    }
+  });
+
+  it("Claims from a Merkle distribution with a valid proof", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const claimant = Keypair.generate();
+    const other = Keypair.generate();
+    const distributionId = Math.floor(Math.random() * 1_000_000);
+    const [distributionPda] = getDistributionPda(vaultPda, distributionId);
+    const [merkleVestingPda] = getMerkleVestingPda(distributionPda, claimant.publicKey);
+    const [claimReceiptPda] = getMerkleClaimReceiptPda(distributionPda, claimant.publicKey);
+
+    const sha256 = (...parts: Buffer[]) =>
+      createHash("sha256").update(Buffer.concat(parts)).digest();
+    const i64 = (value: number) => {
+      const buf = Buffer.alloc(8);
+      buf.writeBigInt64LE(BigInt(value));
+      return buf;
+    };
+    const leaf = (beneficiary: PublicKey, amount: number) =>
+      sha256(
+        Buffer.from([0]),
+        beneficiary.toBuffer(),
+        i64(amount),
+        i64(now - 300),
+        i64(0),
+        i64(600)
+      );
+    const node = (a: Buffer, b: Buffer) =>
+      Buffer.compare(a, b) <= 0
+        ? sha256(Buffer.from([1]), a, b)
+        : sha256(Buffer.from([1]), b, a);
+
+    const claimantLeaf = leaf(claimant.publicKey, 60_000_000);
+    const otherLeaf = leaf(other.publicKey, 40_000_000);
+    const root = node(claimantLeaf, otherLeaf);
+
+    const reservedBefore = (await program.account.vault.fetch(vaultPda)).distributionReserved.toNumber();
+    await program.methods
+      .initializeDistribution(
+        new anchor.BN(distributionId),
+        Array.from(root),
+        new anchor.BN(100_000_000)
+      )
+      .accounts({
+        distribution: distributionPda,
+        vault: vaultPda,
+        mint,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      100_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(100_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const signature = await provider.connection.requestAirdrop(
+      claimant.publicKey,
+      1_000_000_000
+    );
+    await provider.connection.confirmTransaction(signature);
+    const claimantTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      claimant.publicKey
+    );
+
+    const claimAccounts = {
+      vestingAccount: merkleVestingPda,
+      claimReceipt: claimReceiptPda,
+      distribution: distributionPda,
+      vault: vaultPda,
+      vaultTokenAccount,
+      beneficiaryTokenAccount: claimantTokenAccount.address,
+      mint,
+      beneficiary: claimant.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    try {
+      await program.methods
+        .claimMerkle(
+          new anchor.BN(90_000_000),
+          new anchor.BN(now - 300),
+          new anchor.BN(0),
+          new anchor.BN(600),
+          [Array.from(otherLeaf)]
+        )
+        .accounts(claimAccounts)
+        .signers([claimant])
+        .rpc();
+      assert.fail("Should have failed with InvalidProof");
+    } catch (err) {
+      assert.match(err.toString(), /InvalidProof/);
+    }
+
+    await program.methods
+      .claimMerkle(
+        new anchor.BN(60_000_000),
+        new anchor.BN(now - 300),
+        new anchor.BN(0),
+        new anchor.BN(600),
+        [Array.from(otherLeaf)]
+      )
+      .accounts(claimAccounts)
+      .signers([claimant])
+      .rpc();
+
+    const vesting = await program.account.vestingAccount.fetch(merkleVestingPda);
+    const distribution = await program.account.merkleDistribution.fetch(distributionPda);
+    const receipt = await program.account.merkleClaimReceipt.fetch(claimReceiptPda);
+    const claimantAccount = await getAccount(
+      provider.connection,
+      claimantTokenAccount.address
+    );
+    assert.strictEqual(vesting.totalAmount.toNumber(), 60_000_000);
+    assert.strictEqual(distribution.allocatedAmount.toNumber(), 60_000_000);
+    assert.strictEqual(distribution.numGrants.toNumber(), 1);
+    // The unallocated 40M stays reserved and out of the vault's surplus
+    assert.strictEqual(
+      (await program.account.vault.fetch(vaultPda)).distributionReserved.toNumber(),
+      reservedBefore + 40_000_000
+    );
+    assert.ok(receipt.claimed);
+    assert.deepStrictEqual(Buffer.from(receipt.leaf), claimantLeaf);
+    assert.strictEqual(Number(claimantAccount.amount), vesting.claimedAmount.toNumber());
+    assert.ok(vesting.claimedAmount.toNumber() >= 30_000_000);
+
+    // Batch admin actions only accept grants at the standard vesting PDA, like the single-account ones
+    try {
+      await program.methods
+        .batchPause()
+        .accounts({ vault: vaultPda, mint, admin: newAdmin.publicKey })
+        .remainingAccounts([
+          { pubkey: merkleVestingPda, isWritable: true, isSigner: false },
+        ])
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with InvalidAccount");
+    } catch (err) {
+      assert.match(err.toString(), /InvalidAccount/);
+    }
+
+    // Closing the distribution releases the unclaimed 40M and its account
+    try {
+      await program.methods
+        .closeDistribution()
+        .accounts({ distribution: distributionPda, vault: vaultPda, mint, admin: claimant.publicKey })
+        .signers([claimant])
+        .rpc();
+      assert.fail("Should have failed with Unauthorized");
+    } catch (err) {
+      assert.match(err.toString(), /Unauthorized/);
+    }
+    await program.methods
+      .closeDistribution()
+      .accounts({ distribution: distributionPda, vault: vaultPda, mint, admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+    assert.strictEqual(
+      (await program.account.vault.fetch(vaultPda)).distributionReserved.toNumber(),
+      reservedBefore
+    );
+    assert.strictEqual(
+      await program.account.merkleDistribution.fetchNullable(distributionPda),
+      null
+    );
+  });
+
+  it("Merges a Merkle grant into the beneficiary's grant and blocks re-claiming its leaf", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const distributionId = Math.floor(Math.random() * 1_000_000);
+    const [distributionPda] = getDistributionPda(vaultPda, distributionId);
+    const [merkleVestingPda] = getMerkleVestingPda(distributionPda, beneficiary.publicKey);
+    const [claimReceiptPda] = getMerkleClaimReceiptPda(distributionPda, beneficiary.publicKey);
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    const i64 = (value: number) => {
+      const buf = Buffer.alloc(8);
+      buf.writeBigInt64LE(BigInt(value));
+      return buf;
+    };
+    // A single-leaf tree: the root is the leaf and the proof is empty
+    const root = createHash("sha256")
+      .update(
+        Buffer.concat([
+          Buffer.from([0]),
+          beneficiary.publicKey.toBuffer(),
+          i64(60_000_000),
+          i64(now - 300),
+          i64(0),
+          i64(600),
+        ])
+      )
+      .digest();
+
+    await program.methods
+      .initializeDistribution(
+        new anchor.BN(distributionId),
+        Array.from(root),
+        new anchor.BN(60_000_000)
+      )
+      .accounts({
+        distribution: distributionPda,
+        vault: vaultPda,
+        mint,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      100_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(100_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const signature = await provider.connection.requestAirdrop(
+      beneficiary.publicKey,
+      1_000_000_000
+    );
+    await provider.connection.confirmTransaction(signature);
+    const beneficiaryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      beneficiary.publicKey
+    );
+
+    const claimMerkle = () =>
+      program.methods
+        .claimMerkle(
+          new anchor.BN(60_000_000),
+          new anchor.BN(now - 300),
+          new anchor.BN(0),
+          new anchor.BN(600),
+          []
+        )
+        .accounts({
+          vestingAccount: merkleVestingPda,
+          claimReceipt: claimReceiptPda,
+          distribution: distributionPda,
+          vault: vaultPda,
+          vaultTokenAccount,
+          beneficiaryTokenAccount: beneficiaryTokenAccount.address,
+          mint,
+          beneficiary: beneficiary.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([beneficiary])
+        .rpc();
+    await claimMerkle();
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now - 300),
+        new anchor.BN(0),
+        new anchor.BN(600),
+        new anchor.BN(40_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const merkleGrant = await program.account.vestingAccount.fetch(merkleVestingPda);
+    assert.ok(merkleGrant.claimedAmount.toNumber() > 0);
+    const sourceRent = await provider.connection.getBalance(merkleVestingPda);
+    const beneficiaryLamportsBefore = await provider.connection.getBalance(beneficiary.publicKey);
+
+    await program.methods
+      .mergeVesting()
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        admin: newAdmin.publicKey,
+      })
+      .remainingAccounts([
+        { pubkey: merkleVestingPda, isWritable: true, isSigner: false },
+      ])
+      .signers([newAdmin])
+      .rpc();
+
+    const merged = await program.account.vestingAccount.fetch(vestingPda);
+    assert.strictEqual(merged.totalAmount.toNumber(), 100_000_000);
+    assert.strictEqual(merged.scheduleAmount.toNumber(), 100_000_000);
+    assert.strictEqual(
+      merged.claimedAmount.toNumber(),
+      merkleGrant.claimedAmount.toNumber()
+    );
+    assert.strictEqual(
+      await program.account.vestingAccount.fetchNullable(merkleVestingPda),
+      null,
+      "The merged source should be closed"
+    );
+    // The beneficiary paid the Merkle grant's rent in claim_merkle, so it gets it back
+    assert.strictEqual(
+      await provider.connection.getBalance(beneficiary.publicKey),
+      beneficiaryLamportsBefore + sourceRent
+    );
+
+    // The closed source's leaf must not create a second grant
+    try {
+      await claimMerkle();
+      assert.fail("Should have failed with LeafAlreadyClaimed");
+    } catch (err) {
+      assert.match(err.toString(), /LeafAlreadyClaimed/);
+    }
+  });
+
+  it("Batch initializes 10 vesting accounts, the most a legacy transaction fits, with compact args and reports compute", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiaries = Array.from({ length: 10 }, () => Keypair.generate());
+    const pdas = beneficiaries.map((ben) =>
+      getVestingPda(vaultPda, mint, ben.publicKey)
+    );
+
+    const schedule = {
+      startTime: new anchor.BN(now),
+      cliffPeriod: new anchor.BN(60),
+      duration: new anchor.BN(600),
+    };
+    const entries = beneficiaries.map((ben, i) => ({
+      beneficiary: ben.publicKey,
+      totalAmount: new anchor.BN(10_000_000),
+      bump: pdas[i][1],
+    }));
+
+    const builder = program.methods
+      .batchInitializeVestingCompact(schedule, entries)
+      .accounts({
+        vault: vaultPda,
+        mint,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        pdas.map(([pda]) => ({ pubkey: pda, isWritable: true, isSigner: false }))
+      )
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([newAdmin]);
+
+    const simulation = await builder.simulate();
+    const consumedLog = simulation.raw.find((line) =>
+      line.includes(`${program.programId.toBase58()} consumed`)
+    );
+    console.log("Compact batch of 10:", consumedLog);
+
+    await builder.rpc();
+
+    for (const [i, [pda]] of pdas.entries()) {
+      const vesting = await program.account.vestingAccount.fetch(pda);
+      assert.strictEqual(
+        vesting.beneficiary.toBase58(),
+        beneficiaries[i].publicKey.toBase58()
+      );
+      assert.strictEqual(vesting.totalAmount.toNumber(), 10_000_000);
+      assert.strictEqual(vesting.duration.toNumber(), 600);
+      assert.strictEqual(vesting.bump, pdas[i][1]);
+    }
+  });
+  it("Rejects compact batch entries with a non-canonical bump", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    // Find a beneficiary whose vesting PDA also has a valid address at a lower bump
+    let beneficiary: Keypair;
+    let pda: PublicKey;
+    let bump: number;
+    for (;;) {
+      beneficiary = Keypair.generate();
+      const [, canonicalBump] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+      const seeds = [
+        Buffer.from("vesting"),
+        vaultPda.toBuffer(),
+        mint.toBuffer(),
+        beneficiary.publicKey.toBuffer(),
+      ];
+      try {
+        bump = canonicalBump - 1;
+        pda = PublicKey.createProgramAddressSync(
+          [...seeds, Buffer.from([bump])],
+          program.programId
+        );
+        break;
+      } catch {
+        // The next lower bump is on the curve; try another beneficiary
+      }
+    }
+
+    try {
+      await program.methods
+        .batchInitializeVestingCompact(
+          {
+            startTime: new anchor.BN(now),
+            cliffPeriod: new anchor.BN(60),
+            duration: new anchor.BN(600),
+          },
+          [{ beneficiary: beneficiary.publicKey, totalAmount: new anchor.BN(10_000_000), bump }]
+        )
+        .accounts({
+          vault: vaultPda,
+          mint,
+          admin: newAdmin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: pda, isWritable: true, isSigner: false }])
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with NonCanonicalBump");
+    } catch (err) {
+      assert.match(err.toString(), /NonCanonicalBump/);
+    }
+  });
+
+
+  it("Fails compact batch initialization with a wrong bump", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [pda, bump] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    try {
+      await program.methods
+        .batchInitializeVestingCompact(
+          {
+            startTime: new anchor.BN(now),
+            cliffPeriod: new anchor.BN(60),
+            duration: new anchor.BN(600),
+          },
+          [
+            {
+              beneficiary: beneficiary.publicKey,
+              totalAmount: new anchor.BN(10_000_000),
+              bump: (bump + 255) % 256,
+            },
+          ]
+        )
+        .accounts({
+          vault: vaultPda,
+          mint,
+          admin: newAdmin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: pda, isWritable: true, isSigner: false }])
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with InvalidAccount");
+    } catch (err) {
+      assert.match(err.toString(), /InvalidAccount/);
+    }
+  });
+
+  it("Re-runs an idempotent batch, skipping existing grants and rejecting conflicts", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiaries = [Keypair.generate(), Keypair.generate()];
+    const vestingPdas = beneficiaries.map(
+      (ben) => getVestingPda(vaultPda, mint, ben.publicKey)[0]
+    );
+    const argsFor = (amounts: number[]) =>
+      beneficiaries.map((ben, i) => ({
+        beneficiary: ben.publicKey,
+        mint,
+        startTime: new anchor.BN(now),
+        cliffPeriod: new anchor.BN(60),
+        duration: new anchor.BN(600),
+        totalAmount: new anchor.BN(amounts[i]),
+      }));
+    const remainingAccounts = beneficiaries.flatMap((ben, i) => [
+      { pubkey: vestingPdas[i], isWritable: true, isSigner: false },
+      { pubkey: ben.publicKey, isWritable: false, isSigner: false },
+    ]);
+    const send = (amounts: number[], count = beneficiaries.length) =>
+      program.methods
+        .batchInitializeVestingIdempotent(argsFor(amounts).slice(0, count))
+        .accounts({
+          vault: vaultPda,
+          mint,
+          admin: newAdmin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(remainingAccounts.slice(0, count * 2))
+        .signers([newAdmin])
+        .rpc({ commitment: "confirmed" });
+
+    const results: { index: number; status: string }[] = [];
+    const listener = program.addEventListener(
+      "batchItemResultEvent",
+      (event, slot) => {
+        if (vestingPdas.some((pda) => pda.equals(event.vestingAccount))) {
+          results.push({
+            index: event.index,
+            status: Object.keys(event.status)[0],
+          });
+        }
+      }
+    );
+
+    try {
+      // The first run creates only the first grant; re-running the full batch skips it.
+      await send([10_000_000, 20_000_000], 1);
+      await send([10_000_000, 20_000_000]);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      assert.deepStrictEqual(results, [
+        { index: 0, status: "created" },
+        { index: 0, status: "skipped" },
+        { index: 1, status: "created" },
+      ]);
+      for (const [i, amount] of [10_000_000, 20_000_000].entries()) {
+        const vesting = await program.account.vestingAccount.fetch(vestingPdas[i]);
+        assert.strictEqual(vesting.totalAmount.toNumber(), amount);
+      }
+
+      try {
+        await send([10_000_000, 25_000_000]);
+        assert.fail("Should have failed with ConflictingVestingAccount");
+      } catch (err) {
+        assert.match(err.toString(), /ConflictingVestingAccount/);
+      }
+    } finally {
+      await program.removeEventListener(listener);
+    }
+  });
+
+  it("Rejects an idempotent batch entry that matches an irrevocable grant", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now),
+        new anchor.BN(60),
+        new anchor.BN(600),
+        new anchor.BN(10_000_000),
+        true
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    // Same schedule and amount, but batch entries create revocable grants
+    try {
+      await program.methods
+        .batchInitializeVestingIdempotent([
+          {
+            beneficiary: beneficiary.publicKey,
+            mint,
+            startTime: new anchor.BN(now),
+            cliffPeriod: new anchor.BN(60),
+            duration: new anchor.BN(600),
+            totalAmount: new anchor.BN(10_000_000),
+          },
+        ])
+        .accounts({
+          vault: vaultPda,
+          mint,
+          admin: newAdmin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: vestingPda, isWritable: true, isSigner: false },
+          { pubkey: beneficiary.publicKey, isWritable: false, isSigner: false },
+        ])
+        .signers([newAdmin])
+        .rpc();
+      assert.fail("Should have failed with ConflictingVestingAccount");
+    } catch (err) {
+      assert.match(err.toString(), /ConflictingVestingAccount/);
+    }
+  });
+
+  it("Pauses, unpauses and revokes several vesting accounts in one instruction", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiaries = [Keypair.generate(), Keypair.generate()];
+    const vestingPdas = beneficiaries.map(
+      (ben) => getVestingPda(vaultPda, mint, ben.publicKey)[0]
+    );
+
+    await program.methods
+      .batchInitializeVestingV2(
+        beneficiaries.map((ben) => ({
+          beneficiary: ben.publicKey,
+          mint,
+          startTime: new anchor.BN(now),
+          cliffPeriod: new anchor.BN(60),
+          duration: new anchor.BN(600),
+          totalAmount: new anchor.BN(30_000_000),
+        }))
+      )
+      .accounts({
+        vault: vaultPda,
+        mint,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        beneficiaries.flatMap((ben, i) => [
+          { pubkey: vestingPdas[i], isWritable: true, isSigner: false },
+          { pubkey: ben.publicKey, isWritable: false, isSigner: false },
+        ])
+      )
+      .signers([newAdmin])
+      .rpc();
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      60_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(60_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const vestingAccounts = vestingPdas.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+    const adminAccounts = {
+      vault: vaultPda,
+      mint,
+      admin: newAdmin.publicKey,
+    };
+
+    await program.methods
+      .batchPause()
+      .accounts(adminAccounts)
+      .remainingAccounts(vestingAccounts)
+      .signers([newAdmin])
+      .rpc();
+    for (const pda of vestingPdas) {
+      assert.ok((await program.account.vestingAccount.fetch(pda)).paused);
+    }
+
+    await program.methods
+      .batchUnpause()
+      .accounts(adminAccounts)
+      .remainingAccounts(vestingAccounts)
+      .signers([newAdmin])
+      .rpc();
+    for (const pda of vestingPdas) {
+      assert.strictEqual((await program.account.vestingAccount.fetch(pda)).paused, false);
+    }
+
+    const recoveryBefore = await getAccount(provider.connection, recoveryDestination);
+    const revoke = () =>
+      program.methods
+        .batchRevokeVesting()
+        .accounts({
+          vault: vaultPda,
+          vaultTokenAccount,
+          recoveryDestination,
+          mint,
+          admin: newAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(vestingAccounts)
+        .signers([newAdmin])
+        .rpc();
+    await revoke();
+
+    const recoveryAfter = await getAccount(provider.connection, recoveryDestination);
+    assert.strictEqual(
+      Number(recoveryAfter.amount - recoveryBefore.amount),
+      60_000_000
+    );
+    for (const pda of vestingPdas) {
+      const vesting = await program.account.vestingAccount.fetch(pda);
+      assert.ok(vesting.revoked);
+      assert.strictEqual(vesting.totalAmount.toNumber(), 0);
+    }
+
+    try {
+      await revoke();
+      assert.fail("Should have failed with VestingRevoked");
+    } catch (err) {
+      assert.match(err.toString(), /VestingRevoked/);
+    }
+  });
+
+  it("Claims from grants in several vaults at once, skipping ones with nothing to claim", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+
+    // One grant halfway through its schedule, one still before its cliff
+    const schedules = [
+      { start: now - 300, cliff: 0 },
+      { start: now, cliff: 300 },
+    ];
+    const grants = [];
+    for (const { start, cliff } of schedules) {
+      const grantMint = await createMint(
+        provider.connection,
+        admin.payer,
+        admin.publicKey,
+        null,
+        9
+      );
+      const [grantVault] = getVaultPda(grantMint);
+      await program.methods
+        .initializeVault()
+        .accounts({
+          vault: grantVault,
+          mint: grantMint,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const grantVaultTokenAccount = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        grantMint,
+        grantVault,
+        true
+      )).address;
+      const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        grantMint,
+        admin.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        admin.payer,
+        grantMint,
+        sourceTokenAccount.address,
+        admin.publicKey,
+        100_000_000
+      );
+      await program.methods
+        .fundVaultExisting(new anchor.BN(100_000_000))
+        .accounts({
+          vault: grantVault,
+          mint: grantMint,
+          sourceTokenAccount: sourceTokenAccount.address,
+          vaultTokenAccount: grantVaultTokenAccount,
+          admin: admin.publicKey,
+          payer: admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      const [grantVestingPda] = getVestingPda(grantVault, grantMint, beneficiary.publicKey);
+      await program.methods
+        .initializeVesting(
+          new anchor.BN(start),
+          new anchor.BN(cliff),
+          new anchor.BN(600),
+          new anchor.BN(100_000_000),
+          false
+        )
+        .accounts({
+          vestingAccount: grantVestingPda,
+          vault: grantVault,
+          mint: grantMint,
+          beneficiary: beneficiary.publicKey,
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const destination = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        grantMint,
+        beneficiary.publicKey
+      )).address;
+      grants.push([grantVestingPda, grantVault, grantVaultTokenAccount, destination]);
+    }
+
+    const claimMany = (selected: PublicKey[][]) =>
+      program.methods
+        .claimMany()
+        .accounts({
+          beneficiary: beneficiary.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          selected.flat().map((pubkey) => ({
+            pubkey,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers([beneficiary])
+        .rpc({ commitment: "confirmed" });
+
+    const claimManyTx = await claimMany(grants);
+
+    const claimed = await getAccount(provider.connection, grants[0][3]);
+    const skipped = await getAccount(provider.connection, grants[1][3]);
+    const vested = await program.account.vestingAccount.fetch(grants[0][0]);
+    const beforeCliff = await program.account.vestingAccount.fetch(grants[1][0]);
+    assert.ok(Number(claimed.amount) >= 50_000_000);
+    assert.strictEqual(Number(claimed.amount), vested.claimedAmount.toNumber());
+    assert.strictEqual(Number(skipped.amount), 0);
+    assert.strictEqual(beforeCliff.claimedAmount.toNumber(), 0);
+
+    // Only the grant that paid out logs a claim, with its own post-claim state
+    const claimEvents = (await loggedEvents(claimManyTx)).filter((e) => e.name === "claimEvent");
+    assert.strictEqual(claimEvents.length, 1);
+    const claimEvent = claimEvents[0].data as any;
+    const grantVaultAfter = await getAccount(provider.connection, grants[0][2]);
+    assert.strictEqual(claimEvent.vestingAccount.toBase58(), grants[0][0].toBase58());
+    assert.strictEqual(claimEvent.amount.toNumber(), Number(claimed.amount));
+    assert.strictEqual(claimEvent.claimedAmount.toNumber(), vested.claimedAmount.toNumber());
+    assert.strictEqual(claimEvent.totalAmount.toNumber(), vested.totalAmount.toNumber());
+    assert.strictEqual(claimEvent.vaultBalance.toNumber(), Number(grantVaultAfter.amount));
+    assert.ok(claimEvent.timestamp.toNumber() > 0);
+    assert.ok(claimEvent.slot.toNumber() > 0);
+
+    try {
+      await claimMany([grants[1]]);
+      assert.fail("Should have failed with NothingToClaim");
+    } catch (err) {
+      assert.match(err.toString(), /NothingToClaim/);
+    }
+  });
+
+  it("Reports vesting status for paused and revoked grants without failing", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const beneficiary = Keypair.generate();
+    const [vestingPda] = getVestingPda(vaultPda, mint, beneficiary.publicKey);
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      newAdmin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      10_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(10_000_000))
+      .accounts({
+        vault: vaultPda,
+        mint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount,
+        admin: newAdmin.publicKey,
+        payer: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    await program.methods
+      .initializeVesting(
+        new anchor.BN(now),
+        new anchor.BN(300),
+        new anchor.BN(600),
+        new anchor.BN(10_000_000),
+        false
+      )
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: newAdmin.publicKey,
+        admin: newAdmin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const status = () =>
+      program.methods
+        .getVestingStatus()
+        .accounts({ vestingAccount: vestingPda, vault: vaultPda, mint })
+        .view();
+
+    const beforeCliff = await status();
+    assert.deepEqual(beforeCliff.reason, { beforeCliff: {} });
+    assert.strictEqual(beforeCliff.vested.toNumber(), 0);
+    assert.strictEqual(beforeCliff.locked.toNumber(), 10_000_000);
+    assert.strictEqual(beforeCliff.nextUnlockTime.toNumber(), now + 301);
+    assert.strictEqual(beforeCliff.endTime.toNumber(), now + 600);
+
+    // A reduced grant keeps its release rate, so it finishes early: 6M of a 10M/600s schedule takes 360s
+    await program.methods
+      .reduceGrant(new anchor.BN(6_000_000))
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        vaultTokenAccount,
+        mint,
+        recoveryDestination,
+        admin: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc();
+    const reduced = await status();
+    assert.strictEqual(reduced.locked.toNumber(), 6_000_000);
+    assert.strictEqual(reduced.endTime.toNumber(), now + 360);
+
+    await program.methods
+      .pause()
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        mint,
+        admin: newAdmin.publicKey,
+      })
+      .signers([newAdmin])
+      .rpc();
+    const paused = await status();
+    assert.ok(paused.paused);
+    assert.deepEqual(paused.reason, { paused: {} });
+    assert.strictEqual(paused.claimable.toNumber(), 0);
+
+    const revokeTx = await program.methods
+      .revokeVesting()
+      .accounts({
+        vestingAccount: vestingPda,
+        vault: vaultPda,
+        vaultTokenAccount,
+        mint,
+        recoveryDestination,
+        admin: newAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([newAdmin])
+      .rpc({ commitment: "confirmed" });
+    const revoked = await status();
+    assert.ok(revoked.revoked);
+    assert.deepEqual(revoked.reason, { revoked: {} });
+    assert.strictEqual(revoked.locked.toNumber(), 0);
+    assert.strictEqual(revoked.nextUnlockTime.toNumber(), 0);
+
+    // The event carries the grant and vault state the revocation left behind
+    const revokeEvent = await loggedEvent(revokeTx, "revokeVestingEvent");
+    const revokedAccount = await program.account.vestingAccount.fetch(vestingPda);
+    const vaultAfterRevoke = await getAccount(provider.connection, vaultTokenAccount);
+    assert.strictEqual(revokeEvent.beneficiary.toBase58(), beneficiary.publicKey.toBase58());
+    assert.strictEqual(revokeEvent.totalAmount.toNumber(), revokedAccount.totalAmount.toNumber());
+    assert.strictEqual(revokeEvent.claimedAmount.toNumber(), revokedAccount.claimedAmount.toNumber());
+    assert.strictEqual(revokeEvent.vaultBalance.toNumber(), Number(vaultAfterRevoke.amount));
+    assert.ok(revokeEvent.timestamp.toNumber() > 0);
+    assert.ok(revokeEvent.slot.toNumber() > 0);
+  });
+
+  it("Tracks committed, claimed and active grant totals in the vault summary", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const summaryMint = await createMint(
+      provider.connection,
+      admin.payer,
+      admin.publicKey,
+      null,
+      9
+    );
+    const [summaryVault] = getVaultPda(summaryMint);
+    await program.methods
+      .initializeVault()
+      .accounts({
+        vault: summaryVault,
+        mint: summaryMint,
+        payer: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const summaryVaultTokenAccount = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      summaryMint,
+      summaryVault,
+      true
+    )).address;
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      summaryMint,
+      admin.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      summaryMint,
+      sourceTokenAccount.address,
+      admin.publicKey,
+      100_000_000
+    );
+    await program.methods
+      .fundVaultExisting(new anchor.BN(100_000_000))
+      .accounts({
+        vault: summaryVault,
+        mint: summaryMint,
+        sourceTokenAccount: sourceTokenAccount.address,
+        vaultTokenAccount: summaryVaultTokenAccount,
+        admin: admin.publicKey,
+        payer: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // A fully vested grant and one that has not started yet
+    const [vested, pending] = [Keypair.generate(), Keypair.generate()];
+    const vestingPdas = [];
+    for (const [beneficiary, start] of [
+      [vested, now - 1_000],
+      [pending, now + 1_000],
+    ] as [Keypair, number][]) {
+      const [vestingPda] = getVestingPda(summaryVault, summaryMint, beneficiary.publicKey);
+      await program.methods
+        .initializeVesting(
+          new anchor.BN(start),
+          new anchor.BN(0),
+          new anchor.BN(600),
+          new anchor.BN(30_000_000),
+          false
+        )
+        .accounts({
+          vestingAccount: vestingPda,
+          vault: summaryVault,
+          mint: summaryMint,
+          beneficiary: beneficiary.publicKey,
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      vestingPdas.push(vestingPda);
+    }
+
+    const summary = () =>
+      program.methods
+        .getVaultSummary()
+        .accounts({
+          vault: summaryVault,
+          vaultTokenAccount: summaryVaultTokenAccount,
+          mint: summaryMint,
+        })
+        .view();
+
+    let current = await summary();
+    assert.ok(current.admin.equals(admin.publicKey));
+    assert.strictEqual(current.paused, false);
+    assert.strictEqual(current.vaultBalance.toNumber(), 100_000_000);
+    assert.strictEqual(current.totalCommitted.toNumber(), 60_000_000);
+    assert.strictEqual(current.totalClaimed.toNumber(), 0);
+    assert.strictEqual(current.activeGrants.toNumber(), 2);
+    assert.strictEqual(current.distributionReserved.toNumber(), 0);
+    assert.strictEqual(current.surplus.toNumber(), 40_000_000);
+
+    const beneficiaryTokenAccount = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      summaryMint,
+      vested.publicKey
+    )).address;
+    await program.methods
+      .claim()
+      .accounts({
+        vestingAccount: vestingPdas[0],
+        vault: summaryVault,
+        vaultTokenAccount: summaryVaultTokenAccount,
+        mint: summaryMint,
+        beneficiaryTokenAccount,
+        beneficiary: vested.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([vested])
+      .rpc();
+
+    current = await summary();
+    assert.strictEqual(current.totalClaimed.toNumber(), 30_000_000);
+    assert.strictEqual(current.activeGrants.toNumber(), 1);
+    assert.strictEqual(current.surplus.toNumber(), 40_000_000);
+
+    const recovery = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      summaryMint,
+      admin.publicKey
+    )).address;
+    await program.methods
+      .revokeVesting()
+      .accounts({
+        vestingAccount: vestingPdas[1],
+        vault: summaryVault,
+        vaultTokenAccount: summaryVaultTokenAccount,
+        mint: summaryMint,
+        recoveryDestination: recovery,
+        admin: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    current = await summary();
+    assert.strictEqual(current.vaultBalance.toNumber(), 40_000_000);
+    assert.strictEqual(current.totalCommitted.toNumber(), 30_000_000);
+    assert.strictEqual(current.activeGrants.toNumber(), 0);
+    assert.strictEqual(current.surplus.toNumber(), 40_000_000);
+  });
 });